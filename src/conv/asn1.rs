@@ -0,0 +1,537 @@
+use crate::conv::encoding;
+use crate::conv::enum_variants::Encoding;
+use rustc_serialize::hex::ToHex;
+use std::fmt::Write as _;
+
+const MAX_DEPTH: usize = 64;
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum Class {
+    Universal,
+    Application,
+    Context,
+    Private,
+}
+
+/// one tag-length-value, with the children of constructed values and of
+/// OCTET / BIT STRINGs that wrap DER
+pub struct Node<'a> {
+    pub offset: usize,
+    pub header: usize,
+    pub class: Class,
+    pub constructed: bool,
+    pub tag: u32,
+    pub indefinite: bool,
+    pub content: &'a [u8],
+    pub children: Vec<Node<'a>>,
+}
+
+pub const BOOLEAN: u32 = 1;
+pub const INTEGER: u32 = 2;
+pub const BIT_STRING: u32 = 3;
+pub const OCTET_STRING: u32 = 4;
+pub const OID: u32 = 6;
+pub const SEQUENCE: u32 = 16;
+
+impl<'a> Node<'a> {
+    pub fn is(&self, tag: u32) -> bool { self.class == Class::Universal && self.tag == tag }
+
+    pub fn is_context(&self, tag: u32) -> bool { self.class == Class::Context && self.tag == tag }
+
+    pub fn child(&self, i: usize) -> Result<&Node<'a>, String> {
+        self.children
+            .get(i)
+            .ok_or_else(|| format!("missing element {} at offset {}", i, self.offset))
+    }
+
+    /// the whole encoding, header included
+    pub fn der(&self, input: &'a [u8]) -> &'a [u8] {
+        let eoc = if self.indefinite { 2 } else { 0 };
+        &input[self.offset..self.offset + self.header + self.content.len() + eoc]
+    }
+
+    /// contents of a BIT STRING without the unused-bits byte
+    pub fn bits(&self) -> &'a [u8] { self.content.get(1..).unwrap_or_default() }
+
+    /// dotted decimal with the known name, ex: 'sha256WithRSAEncryption (1.2.840.113549.1.1.11)'
+    pub fn oid_named(&self) -> String {
+        let oid = oid(self.content);
+        match oid_name(&oid) {
+            Some(name) => format!("{} ({})", name, oid),
+            None => oid,
+        }
+    }
+
+    /// the value as the dump shows it
+    pub fn text(&self) -> String { value(self) }
+}
+
+/// the DER blocks in PEM text, or the bytes of hex, base64 or raw input
+pub fn input(text: &str) -> Result<Vec<(String, Vec<u8>)>, String> {
+    if text.contains("-----BEGIN ") {
+        return pem(text);
+    }
+    let compact = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ':')
+        .collect::<String>();
+    if compact.is_empty() {
+        return Err("no input".to_owned());
+    }
+    let bytes = if compact.len() % 2 == 0 && compact.chars().all(|c| c.is_ascii_hexdigit()) {
+        encoding::decode(&compact, Encoding::Hex)?
+    } else {
+        encoding::decode(&compact, Encoding::Base64)
+            .unwrap_or_else(|_| text.as_bytes().to_vec())
+    };
+    Ok(vec![(String::new(), bytes)])
+}
+
+pub fn pem(text: &str) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let mut blocks = vec![];
+    let mut lines = text.lines().map(str::trim);
+    while let Some(line) = lines.next() {
+        let Some(label) = line
+            .strip_prefix("-----BEGIN ")
+            .and_then(|l| l.strip_suffix("-----"))
+        else {
+            continue;
+        };
+        let end = format!("-----END {}-----", label);
+        let body = lines
+            .by_ref()
+            .take_while(|l| *l != end)
+            // rfc 1421 headers such as Proc-Type
+            .filter(|l| !l.contains(':'))
+            .collect::<String>();
+        let bytes = encoding::decode(&body, Encoding::Base64)
+            .map_err(|e| format!("{}: {}", label, e))?;
+        blocks.push((label.to_owned(), bytes));
+    }
+    match blocks.is_empty() {
+        true => Err("no PEM block found".to_owned()),
+        false => Ok(blocks),
+    }
+}
+
+pub fn parse(bytes: &[u8]) -> Result<Vec<Node<'_>>, String> { parse_all(bytes, 0, 0) }
+
+fn parse_all(bytes: &[u8], base: usize, depth: usize) -> Result<Vec<Node<'_>>, String> {
+    let mut nodes = vec![];
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let (node, used) = parse_one(&bytes[pos..], base + pos, depth)?;
+        nodes.push(node);
+        pos += used;
+    }
+    Ok(nodes)
+}
+
+/// parses a single value, returning it with the number of bytes it spans
+fn parse_one(bytes: &[u8], offset: usize, depth: usize) -> Result<(Node<'_>, usize), String> {
+    if depth > MAX_DEPTH {
+        return Err(format!("nested too deeply at offset {}", offset));
+    }
+    let truncated = || format!("truncated header at offset {}", offset);
+    let first = *bytes.first().ok_or_else(truncated)?;
+    let class = match first >> 6 {
+        0 => Class::Universal,
+        1 => Class::Application,
+        2 => Class::Context,
+        _ => Class::Private,
+    };
+    let constructed = first & 0x20 != 0;
+    let mut pos = 1;
+    let mut tag = (first & 0x1f) as u32;
+    if tag == 0x1f {
+        tag = 0;
+        loop {
+            let b = *bytes.get(pos).ok_or_else(truncated)?;
+            pos += 1;
+            tag = tag.checked_mul(128).ok_or("tag number too large")? | (b & 0x7f) as u32;
+            if b & 0x80 == 0 {
+                break;
+            }
+        }
+    }
+    let b = *bytes.get(pos).ok_or_else(truncated)?;
+    pos += 1;
+    let len = match b {
+        0x80 if constructed => None,
+        0x80 => return Err(format!("indefinite length on a primitive at offset {}", offset)),
+        b if b & 0x80 == 0 => Some(b as usize),
+        b => {
+            let n = (b & 0x7f) as usize;
+            if n > 8 {
+                return Err(format!("length of {} bytes at offset {}", n, offset));
+            }
+            let digits = bytes.get(pos..pos + n).ok_or_else(truncated)?;
+            pos += n;
+            Some(digits.iter().fold(0usize, |acc, &d| acc << 8 | d as usize))
+        },
+    };
+    let header = pos;
+    let mut node = Node {
+        offset,
+        header,
+        class,
+        constructed,
+        tag,
+        indefinite: len.is_none(),
+        content: &[],
+        children: vec![],
+    };
+    let used = match len {
+        Some(len) => {
+            let end = header.checked_add(len).filter(|&end| end <= bytes.len()).ok_or_else(|| {
+                format!("length {} at offset {} runs past the end of the input", len, offset)
+            })?;
+            node.content = &bytes[header..end];
+            if constructed {
+                node.children = parse_all(node.content, offset + header, depth + 1)?;
+            } else {
+                node.children = encapsulated(&node, depth);
+            }
+            end
+        },
+        // BER, children up to the end-of-contents marker
+        None => {
+            let mut pos = header;
+            loop {
+                if bytes.get(pos..pos + 2) == Some(&[0u8, 0][..]) {
+                    break;
+                }
+                if pos >= bytes.len() {
+                    return Err(format!("missing end-of-contents for offset {}", offset));
+                }
+                let (child, used) = parse_one(&bytes[pos..], offset + pos, depth + 1)?;
+                node.children.push(child);
+                pos += used;
+            }
+            node.content = &bytes[header..pos];
+            pos + 2
+        },
+    };
+    Ok((node, used))
+}
+
+/// DER wrapped in an OCTET STRING or BIT STRING, as in extensions and public keys
+fn encapsulated<'a>(node: &Node<'a>, depth: usize) -> Vec<Node<'a>> {
+    let content = node.content;
+    let (inner, skip) = match node.tag {
+        BIT_STRING if content.first() == Some(&0) => (&content[1..], 1),
+        OCTET_STRING => (content, 0),
+        _ => return vec![],
+    };
+    if node.class != Class::Universal || inner.len() < 2 {
+        return vec![];
+    }
+    match parse_one(inner, node.offset + node.header + skip, depth + 1) {
+        Ok((child, used)) if used == inner.len() => vec![child],
+        _ => vec![],
+    }
+}
+
+/// an `openssl asn1parse`-like listing
+pub fn dump(text: &str) -> Result<String, String> {
+    let mut out = String::new();
+    for (label, bytes) in input(text)? {
+        if !label.is_empty() {
+            let _ = writeln!(out, "-----{}-----", label);
+        }
+        for node in parse(&bytes)? {
+            dump_node(&mut out, &node, 0);
+        }
+    }
+    Ok(out)
+}
+
+fn dump_node(out: &mut String, node: &Node, depth: usize) {
+    let len = match node.indefinite {
+        true => "inf".to_owned(),
+        false => node.content.len().to_string(),
+    };
+    let kind = if node.constructed { "cons" } else { "prim" };
+    let name = format!("{}{}", " ".repeat(depth), tag_name(node));
+    let _ = write!(
+        out,
+        "{:>5}:d={:<2} hl={} l={:>4} {}: ",
+        node.offset, depth, node.header, len, kind
+    );
+    let _ = match node.constructed {
+        true => writeln!(out, "{}", name),
+        false => writeln!(out, "{:<20}:{}", name, value(node)),
+    };
+    for child in &node.children {
+        dump_node(out, child, depth + 1);
+    }
+}
+
+fn tag_name(node: &Node) -> String {
+    let prefix = match node.class {
+        Class::Universal => return universal_name(node.tag).to_owned(),
+        Class::Application => "appl",
+        Class::Context => "cont",
+        Class::Private => "priv",
+    };
+    format!("{} [ {} ]", prefix, node.tag)
+}
+
+fn universal_name(tag: u32) -> &'static str {
+    match tag {
+        0 => "EOC",
+        1 => "BOOLEAN",
+        2 => "INTEGER",
+        3 => "BIT STRING",
+        4 => "OCTET STRING",
+        5 => "NULL",
+        6 => "OBJECT",
+        7 => "OBJECT DESCRIPTOR",
+        8 => "EXTERNAL",
+        9 => "REAL",
+        10 => "ENUMERATED",
+        12 => "UTF8STRING",
+        13 => "RELATIVE OID",
+        16 => "SEQUENCE",
+        17 => "SET",
+        18 => "NUMERICSTRING",
+        19 => "PRINTABLESTRING",
+        20 => "T61STRING",
+        21 => "VIDEOTEXSTRING",
+        22 => "IA5STRING",
+        23 => "UTCTIME",
+        24 => "GENERALIZEDTIME",
+        25 => "GRAPHICSTRING",
+        26 => "VISIBLESTRING",
+        27 => "GENERALSTRING",
+        28 => "UNIVERSALSTRING",
+        30 => "BMPSTRING",
+        _ => "UNKNOWN",
+    }
+}
+
+fn value(node: &Node) -> String {
+    let c = node.content;
+    if node.class != Class::Universal {
+        return c.to_hex().to_uppercase();
+    }
+    match node.tag {
+        1 => match c {
+            [0] => "FALSE".to_owned(),
+            [_] => "TRUE".to_owned(),
+            _ => format!("invalid {}", c.to_hex()),
+        },
+        2 | 10 => integer(c),
+        3 => match c.split_first() {
+            Some((0, bits)) => bits.to_hex().to_uppercase(),
+            Some((unused, bits)) => {
+                format!("{} ({} unused bits)", bits.to_hex().to_uppercase(), unused)
+            },
+            None => String::new(),
+        },
+        5 => String::new(),
+        6 => node.oid_named(),
+        12 | 18 | 19 | 20 | 21 | 22 | 23 | 24 | 25 | 26 | 27 => {
+            String::from_utf8_lossy(c).into_owned()
+        },
+        30 => {
+            let units = c.chunks(2).map(|u| u16::from_be_bytes([u[0], *u.get(1).unwrap_or(&0)]));
+            char::decode_utf16(units)
+                .map(|r| r.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect()
+        },
+        28 => c
+            .chunks(4)
+            .map(|u| {
+                let mut b = [0; 4];
+                b[..u.len()].copy_from_slice(u);
+                char::from_u32(u32::from_be_bytes(b)).unwrap_or(char::REPLACEMENT_CHARACTER)
+            })
+            .collect(),
+        _ => c.to_hex().to_uppercase(),
+    }
+}
+
+/// decimal when it fits in an i128, hex otherwise
+pub fn integer(c: &[u8]) -> String {
+    if c.is_empty() || c.len() > 16 {
+        return c.to_hex().to_uppercase();
+    }
+    let fill = if c[0] & 0x80 != 0 { 0xff } else { 0 };
+    let mut b = [fill; 16];
+    b[16 - c.len()..].copy_from_slice(c);
+    i128::from_be_bytes(b).to_string()
+}
+
+/// dotted decimal form of an OBJECT IDENTIFIER
+pub fn oid(c: &[u8]) -> String {
+    let mut arcs = vec![];
+    let mut arc = 0u128;
+    for &b in c {
+        arc = arc << 7 | (b & 0x7f) as u128;
+        if b & 0x80 == 0 {
+            if arcs.is_empty() {
+                let first = (arc / 40).min(2);
+                arcs.push(first);
+                arcs.push(arc - first * 40);
+            } else {
+                arcs.push(arc);
+            }
+            arc = 0;
+        }
+    }
+    arcs.iter().map(u128::to_string).collect::<Vec<_>>().join(".")
+}
+
+/// DER encoding of a dotted decimal OBJECT IDENTIFIER
+pub fn encode_oid(dotted: &str) -> Vec<u8> {
+    let arcs = dotted.split('.').filter_map(|a| a.parse::<u128>().ok()).collect::<Vec<_>>();
+    let mut content = vec![];
+    let first = arcs.first().copied().unwrap_or(0) * 40 + arcs.get(1).copied().unwrap_or(0);
+    for arc in std::iter::once(first).chain(arcs.iter().skip(2).copied()) {
+        let mut groups = vec![(arc & 0x7f) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            groups.push((rest & 0x7f) as u8 | 0x80);
+            rest >>= 7;
+        }
+        content.extend(groups.iter().rev());
+    }
+    tlv(OID as u8, &content)
+}
+
+/// tag, DER length and content
+pub fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    match content.len() {
+        len if len < 0x80 => out.push(len as u8),
+        len => {
+            let digits = len.to_be_bytes();
+            let digits = &digits[digits.iter().position(|&b| b != 0).unwrap_or(0)..];
+            out.push(0x80 | digits.len() as u8);
+            out.extend(digits);
+        },
+    }
+    out.extend(content);
+    out
+}
+
+/// a non-negative INTEGER from big-endian magnitude bytes
+pub fn unsigned(magnitude: &[u8]) -> Vec<u8> {
+    let start = magnitude.iter().position(|&b| b != 0).unwrap_or(magnitude.len());
+    let mut content = magnitude[start..].to_vec();
+    if !matches!(content.first(), Some(b) if b & 0x80 == 0) {
+        content.insert(0, 0);
+    }
+    tlv(INTEGER as u8, &content)
+}
+
+pub fn sequence(parts: &[Vec<u8>]) -> Vec<u8> { tlv(0x30, &parts.concat()) }
+
+pub fn oid_name(oid: &str) -> Option<&'static str> {
+    OIDS.iter().find(|(o, _)| *o == oid).map(|(_, name)| *name)
+}
+
+const OIDS: &[(&str, &str)] = &[
+    // algorithms
+    ("1.2.840.113549.1.1.1", "rsaEncryption"),
+    ("1.2.840.113549.1.1.4", "md5WithRSAEncryption"),
+    ("1.2.840.113549.1.1.5", "sha1WithRSAEncryption"),
+    ("1.2.840.113549.1.1.10", "rsassaPss"),
+    ("1.2.840.113549.1.1.11", "sha256WithRSAEncryption"),
+    ("1.2.840.113549.1.1.12", "sha384WithRSAEncryption"),
+    ("1.2.840.113549.1.1.13", "sha512WithRSAEncryption"),
+    ("1.2.840.113549.1.1.7", "rsaesOaep"),
+    ("1.2.840.113549.1.1.8", "mgf1"),
+    ("1.2.840.10040.4.1", "dsaEncryption"),
+    ("1.2.840.10040.4.3", "dsaWithSHA1"),
+    ("1.2.840.10045.2.1", "ecPublicKey"),
+    ("1.2.840.10045.3.1.7", "prime256v1"),
+    ("1.3.132.0.10", "secp256k1"),
+    ("1.3.132.0.34", "secp384r1"),
+    ("1.3.132.0.35", "secp521r1"),
+    ("1.2.840.10045.4.1", "ecdsa-with-SHA1"),
+    ("1.2.840.10045.4.3.2", "ecdsa-with-SHA256"),
+    ("1.2.840.10045.4.3.3", "ecdsa-with-SHA384"),
+    ("1.2.840.10045.4.3.4", "ecdsa-with-SHA512"),
+    ("1.3.101.110", "X25519"),
+    ("1.3.101.111", "X448"),
+    ("1.3.101.112", "ED25519"),
+    ("1.3.101.113", "ED448"),
+    ("1.3.14.3.2.26", "sha1"),
+    ("2.16.840.1.101.3.4.2.1", "sha256"),
+    ("2.16.840.1.101.3.4.2.2", "sha384"),
+    ("2.16.840.1.101.3.4.2.3", "sha512"),
+    ("2.16.840.1.101.3.4.2.4", "sha224"),
+    ("1.2.840.113549.2.5", "md5"),
+    ("1.2.840.113549.2.7", "hmacWithSHA1"),
+    ("1.2.840.113549.2.9", "hmacWithSHA256"),
+    ("1.2.840.113549.2.11", "hmacWithSHA512"),
+    ("2.16.840.1.101.3.4.1.2", "aes-128-cbc"),
+    ("2.16.840.1.101.3.4.1.22", "aes-192-cbc"),
+    ("2.16.840.1.101.3.4.1.42", "aes-256-cbc"),
+    ("1.2.840.113549.3.7", "des-ede3-cbc"),
+    ("1.2.840.113549.1.5.12", "PBKDF2"),
+    ("1.2.840.113549.1.5.13", "PBES2"),
+    // pkcs #7 / #9
+    ("1.2.840.113549.1.7.1", "pkcs7-data"),
+    ("1.2.840.113549.1.7.2", "pkcs7-signedData"),
+    ("1.2.840.113549.1.7.3", "pkcs7-envelopedData"),
+    ("1.2.840.113549.1.7.6", "pkcs7-encryptedData"),
+    ("1.2.840.113549.1.9.1", "emailAddress"),
+    ("1.2.840.113549.1.9.3", "contentType"),
+    ("1.2.840.113549.1.9.4", "messageDigest"),
+    ("1.2.840.113549.1.9.5", "signingTime"),
+    ("1.2.840.113549.1.9.7", "challengePassword"),
+    ("1.2.840.113549.1.9.14", "extensionRequest"),
+    ("1.2.840.113549.1.9.20", "friendlyName"),
+    ("1.2.840.113549.1.9.21", "localKeyID"),
+    ("1.2.840.113549.1.12.10.1.2", "pkcs8ShroudedKeyBag"),
+    ("1.2.840.113549.1.12.10.1.3", "certBag"),
+    // distinguished names
+    ("2.5.4.3", "commonName"),
+    ("2.5.4.4", "surname"),
+    ("2.5.4.5", "serialNumber"),
+    ("2.5.4.6", "countryName"),
+    ("2.5.4.7", "localityName"),
+    ("2.5.4.8", "stateOrProvinceName"),
+    ("2.5.4.9", "streetAddress"),
+    ("2.5.4.10", "organizationName"),
+    ("2.5.4.11", "organizationalUnitName"),
+    ("2.5.4.12", "title"),
+    ("2.5.4.42", "givenName"),
+    ("2.5.4.97", "organizationIdentifier"),
+    ("0.9.2342.19200300.100.1.1", "userId"),
+    ("0.9.2342.19200300.100.1.25", "domainComponent"),
+    ("1.3.6.1.4.1.311.60.2.1.3", "jurisdictionCountryName"),
+    ("2.5.4.15", "businessCategory"),
+    // extensions
+    ("2.5.29.14", "X509v3 Subject Key Identifier"),
+    ("2.5.29.15", "X509v3 Key Usage"),
+    ("2.5.29.17", "X509v3 Subject Alternative Name"),
+    ("2.5.29.18", "X509v3 Issuer Alternative Name"),
+    ("2.5.29.19", "X509v3 Basic Constraints"),
+    ("2.5.29.30", "X509v3 Name Constraints"),
+    ("2.5.29.31", "X509v3 CRL Distribution Points"),
+    ("2.5.29.32", "X509v3 Certificate Policies"),
+    ("2.5.29.35", "X509v3 Authority Key Identifier"),
+    ("2.5.29.37", "X509v3 Extended Key Usage"),
+    ("2.5.29.32.0", "X509v3 Any Policy"),
+    ("1.3.6.1.5.5.7.1.1", "Authority Information Access"),
+    ("1.3.6.1.4.1.11129.2.4.2", "CT Precertificate SCTs"),
+    ("1.3.6.1.4.1.11129.2.4.3", "CT Precertificate Poison"),
+    ("1.3.6.1.5.5.7.48.1", "OCSP"),
+    ("1.3.6.1.5.5.7.48.2", "CA Issuers"),
+    ("1.3.6.1.5.5.7.3.1", "TLS Web Server Authentication"),
+    ("1.3.6.1.5.5.7.3.2", "TLS Web Client Authentication"),
+    ("1.3.6.1.5.5.7.3.3", "Code Signing"),
+    ("1.3.6.1.5.5.7.3.4", "E-mail Protection"),
+    ("1.3.6.1.5.5.7.3.8", "Time Stamping"),
+    ("1.3.6.1.5.5.7.3.9", "OCSP Signing"),
+    ("2.23.140.1.1", "CA/B EV"),
+    ("2.23.140.1.2.1", "CA/B Domain Validated"),
+    ("2.23.140.1.2.2", "CA/B Organization Validated"),
+    ("2.23.140.1.2.3", "CA/B Individual Validated"),
+    ("1.3.6.1.5.5.7.2.1", "CPS"),
+    ("1.3.6.1.5.5.7.2.2", "User Notice"),
+];
@@ -0,0 +1,91 @@
+use itertools::Itertools;
+
+/// the multipliers coprime with 26, the only ones with an inverse
+pub const AFFINE_A: [i32; 12] = [1, 3, 5, 7, 9, 11, 15, 17, 19, 21, 23, 25];
+
+pub struct AffineKey {
+    pub a: i32,
+    pub b: i32,
+}
+
+impl Default for AffineKey {
+    fn default() -> Self {
+        Self {
+            a: 1,
+            b: 0,
+        }
+    }
+}
+
+#[inline]
+fn shift_alpha(c: char, f: impl Fn(i32) -> i32) -> char {
+    let base = match c {
+        'a'..='z' => b'a',
+        'A'..='Z' => b'A',
+        _ => return c,
+    };
+    let x = f((c as u8 - base) as i32).rem_euclid(26);
+    (base + x as u8) as char
+}
+
+pub fn caesar(text: &str, shift: i32) -> String {
+    text.chars().map(|c| shift_alpha(c, |x| x + shift)).collect()
+}
+
+pub fn rot13(text: &str) -> String { caesar(text, 13) }
+
+/// rotates the printable ascii range '!'..='~'
+pub fn rot47(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '!'..='~' => (b'!' + (c as u8 - b'!' + 47) % 94) as char,
+            _ => c,
+        })
+        .collect()
+}
+
+pub fn caesar_brute_force(text: &str) -> String {
+    (1..26)
+        .map(|shift| format!("{:02}: {}", shift, caesar(text, -shift)))
+        .join("\n")
+}
+
+pub fn vigenere(text: &str, key: &str, decode: bool) -> Result<String, String> {
+    let key = key
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| (c.to_ascii_lowercase() as u8 - b'a') as i32)
+        .collect::<Vec<_>>();
+    if key.is_empty() {
+        return Err("key must contain letters".to_owned());
+    }
+
+    // the key only advances on letters
+    let mut k = key.iter().cycle();
+    Ok(text
+        .chars()
+        .map(|c| match c.is_ascii_alphabetic() {
+            true => {
+                let shift = *k.next().unwrap();
+                shift_alpha(c, |x| if decode { x - shift } else { x + shift })
+            },
+            false => c,
+        })
+        .collect())
+}
+
+pub fn atbash(text: &str) -> String { text.chars().map(|c| shift_alpha(c, |x| 25 - x)).collect() }
+
+pub fn affine(text: &str, key: &AffineKey, decode: bool) -> Result<String, String> {
+    let (a, b) = (key.a, key.b);
+    let a_inv = (1..26)
+        .find(|x| (a * x).rem_euclid(26) == 1)
+        .ok_or_else(|| format!("a = {} is not coprime with 26", a))?;
+    Ok(text
+        .chars()
+        .map(|c| match decode {
+            true => shift_alpha(c, |x| a_inv * (x - b)),
+            false => shift_alpha(c, |x| a * x + b),
+        })
+        .collect())
+}
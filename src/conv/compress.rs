@@ -0,0 +1,206 @@
+use crate::conv::enum_variants::{Codec, Encoding};
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+use flate2::{Compression, Decompress, FlushDecompress, Status};
+use rustc_serialize::hex::ToHex;
+use std::io::{Read, Write};
+use std::ops::RangeInclusive;
+use xz2::read::XzDecoder;
+use xz2::stream::{LzmaOptions, Stream, CONCATENATED};
+use xz2::write::XzEncoder;
+
+/// decompressed size limit, a few KB of input can expand to gigabytes
+const MAX_OUTPUT: u64 = 64 << 20;
+
+/// memory limit of the xz and lzma decoders
+const MAX_DECODER_MEMORY: u64 = 256 << 20;
+
+#[derive(Clone, PartialEq)]
+pub struct CompressParams {
+    pub level: u32,
+    /// encoding of the uncompressed side
+    pub plain: Encoding,
+    /// encoding of the compressed side
+    pub packed: Encoding,
+}
+
+impl Default for CompressParams {
+    fn default() -> Self {
+        Self {
+            level: Compression::default().level(),
+            plain: Encoding::Text,
+            packed: Encoding::Base64,
+        }
+    }
+}
+
+/// compression levels accepted by the codec, None when it has no level
+pub fn level_range(codec: Codec) -> Option<RangeInclusive<u32>> {
+    match codec {
+        Codec::Gzip | Codec::Zlib | Codec::Deflate | Codec::Xz | Codec::Lzma => Some(0..=9),
+        Codec::Bzip2 => Some(1..=9),
+        Codec::Zstd => Some(1..=22),
+        Codec::Brotli => Some(0..=11),
+        Codec::Lz4 => None,
+    }
+}
+
+pub fn compress(codec: Codec, data: &[u8], level: u32) -> Result<Vec<u8>, String> {
+    let level = level_range(codec).map_or(level, |r| level.clamp(*r.start(), *r.end()));
+    let flate = Compression::new(level);
+    match codec {
+        Codec::Gzip => finish(GzEncoder::new(Vec::new(), flate), data, GzEncoder::finish),
+        Codec::Zlib => finish(ZlibEncoder::new(Vec::new(), flate), data, ZlibEncoder::finish),
+        Codec::Deflate => {
+            finish(DeflateEncoder::new(Vec::new(), flate), data, DeflateEncoder::finish)
+        },
+        Codec::Zstd => zstd::encode_all(data, level as i32).map_err(|e| e.to_string()),
+        Codec::Brotli => {
+            let mut enc = brotli::CompressorWriter::new(Vec::new(), 4096, level, 22);
+            enc.write_all(data).map_err(|e| e.to_string())?;
+            Ok(enc.into_inner())
+        },
+        Codec::Lz4 => {
+            let mut enc = lz4_flex::frame::FrameEncoder::new(Vec::new());
+            enc.write_all(data).map_err(|e| e.to_string())?;
+            enc.finish().map_err(|e| e.to_string())
+        },
+        Codec::Bzip2 => {
+            let enc = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::new(level));
+            finish(enc, data, bzip2::write::BzEncoder::finish)
+        },
+        Codec::Xz => finish(XzEncoder::new(Vec::new(), level), data, XzEncoder::finish),
+        Codec::Lzma => {
+            let opts = LzmaOptions::new_preset(level).map_err(|e| e.to_string())?;
+            let stream = Stream::new_lzma_encoder(&opts).map_err(|e| e.to_string())?;
+            finish(XzEncoder::new_stream(Vec::new(), stream), data, XzEncoder::finish)
+        },
+    }
+}
+
+pub fn decompress(codec: Codec, data: &[u8]) -> Result<Vec<u8>, String> {
+    let reader: Box<dyn Read + '_> = match codec {
+        Codec::Gzip => Box::new(GzDecoder::new(data)),
+        Codec::Zlib => Box::new(ZlibDecoder::new(data)),
+        Codec::Deflate => Box::new(DeflateDecoder::new(data)),
+        Codec::Zstd => Box::new(zstd::Decoder::new(data).map_err(|e| e.to_string())?),
+        Codec::Brotli => Box::new(brotli::Decompressor::new(data, 4096)),
+        Codec::Lz4 => Box::new(lz4_flex::frame::FrameDecoder::new(data)),
+        Codec::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(data)),
+        Codec::Xz => {
+            let stream = Stream::new_stream_decoder(MAX_DECODER_MEMORY, CONCATENATED)
+                .map_err(|e| e.to_string())?;
+            Box::new(XzDecoder::new_stream(data, stream))
+        },
+        Codec::Lzma => {
+            let stream =
+                Stream::new_lzma_decoder(MAX_DECODER_MEMORY).map_err(|e| e.to_string())?;
+            Box::new(XzDecoder::new_stream(data, stream))
+        },
+    };
+    let mut buf = Vec::new();
+    reader
+        .take(MAX_OUTPUT + 1)
+        .read_to_end(&mut buf)
+        .map_err(|e| e.to_string())?;
+    if buf.len() as u64 > MAX_OUTPUT {
+        return Err(format!("output truncated at {} bytes", MAX_OUTPUT));
+    }
+    Ok(buf)
+}
+
+/// gzip member header (rfc 1952), read without decompressing the body so truncated or
+/// corrupt streams still show it
+pub fn gzip_header(data: &[u8]) -> Result<String, String> {
+    let mut dec = GzDecoder::new(data);
+    // the header is parsed by the first read, the body may well be broken
+    let read = dec.read(&mut [0; 1]);
+    let header = match (dec.header(), read) {
+        (Some(header), _) => header,
+        (None, Err(e)) => return Err(e.to_string()),
+        (None, Ok(_)) => return Err("not a gzip stream".to_owned()),
+    };
+
+    let mut lines = vec![];
+    if let Some(name) = header.filename() {
+        lines.push(format!("filename: {}", String::from_utf8_lossy(name)));
+    }
+    lines.push(format!("mtime: {}", header.mtime()));
+    lines.push(format!("os: {}", os_name(header.operating_system())));
+    if let Some(comment) = header.comment() {
+        lines.push(format!("comment: {}", String::from_utf8_lossy(comment)));
+    }
+    if let Some(extra) = header.extra() {
+        lines.push(format!("extra: {}", extra.to_hex()));
+    }
+    lines.push(format!("compressed size: {}", data.len()));
+    // the trailer is only where we expect it when a single member fills the input
+    let body = 10
+        + header.extra().map_or(0, |e| 2 + e.len())
+        + header.filename().map_or(0, |n| n.len() + 1)
+        + header.comment().map_or(0, |c| c.len() + 1)
+        + if data[3] & 2 != 0 { 2 } else { 0 };
+    match deflate_end(data, body) {
+        Some(end) if end + 8 == data.len() => {
+            let isize = &data[end + 4..];
+            let isize = u32::from_le_bytes([isize[0], isize[1], isize[2], isize[3]]);
+            lines.push(format!("ISIZE (mod 2^32): {}", isize));
+        },
+        Some(_) => lines.push("ISIZE: not shown, the input has more than one member".to_owned()),
+        None => {
+            lines.push("ISIZE: not shown, the deflate stream is truncated or too large".to_owned())
+        },
+    }
+    Ok(lines.join("\n"))
+}
+
+/// offset just past the raw deflate stream starting at `start`, None when it does not end
+fn deflate_end(data: &[u8], start: usize) -> Option<usize> {
+    let mut inflater = Decompress::new(false);
+    let mut scratch = vec![0; 1 << 16];
+    let input = data.get(start..)?;
+    loop {
+        let (before_in, before_out) = (inflater.total_in(), inflater.total_out());
+        let consumed = before_in as usize;
+        let status = inflater
+            .decompress(&input[consumed..], &mut scratch, FlushDecompress::None)
+            .ok()?;
+        if status == Status::StreamEnd {
+            return Some(start + inflater.total_in() as usize);
+        }
+        let stuck = inflater.total_in() == before_in && inflater.total_out() == before_out;
+        if stuck || inflater.total_out() > MAX_OUTPUT {
+            return None;
+        }
+    }
+}
+
+fn finish<W: Write>(
+    mut enc: W,
+    data: &[u8],
+    f: impl FnOnce(W) -> std::io::Result<Vec<u8>>,
+) -> Result<Vec<u8>, String> {
+    enc.write_all(data).map_err(|e| e.to_string())?;
+    f(enc).map_err(|e| e.to_string())
+}
+
+fn os_name(os: u8) -> String {
+    match os {
+        0 => "FAT".to_owned(),
+        1 => "Amiga".to_owned(),
+        2 => "VMS".to_owned(),
+        3 => "Unix".to_owned(),
+        4 => "VM/CMS".to_owned(),
+        5 => "Atari TOS".to_owned(),
+        6 => "HPFS".to_owned(),
+        7 => "Macintosh".to_owned(),
+        8 => "Z-System".to_owned(),
+        9 => "CP/M".to_owned(),
+        10 => "TOPS-20".to_owned(),
+        11 => "NTFS".to_owned(),
+        12 => "QDOS".to_owned(),
+        13 => "Acorn RISCOS".to_owned(),
+        255 => "unknown".to_owned(),
+        _ => format!("{} (reserved)", os),
+    }
+}
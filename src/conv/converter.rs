@@ -1,23 +1,58 @@
 use base64::engine::general_purpose;
 use base64::{alphabet, engine, Engine as _};
 use eframe::egui;
-use egui::{vec2, Align, Layout, TextStyle, Ui};
-use std::io::Write;
+use egui::{vec2, Align, Layout, RichText, TextStyle, Ui};
 
-use crate::conv::enum_variants::{Base64Kind, BinaryKind, Conv, EscapeKind};
-use crate::conv::hasher::hasher;
-use crate::conv::{enum_variants, Editor};
+use crate::conv::editor::Selected;
+use crate::conv::enum_variants::{
+    Asn1Kind, Base64Kind, BinaryKind, ClassicCipher, Codec, CompressMode, Conv, DataKind, Digest,
+    Direction, Encoding, EscapeKind, NumberKind, XorKind,
+};
+use crate::conv::file_hash::{Digests, FileHash};
+use crate::conv::hasher::{digest_bytes, format_digest, match_digest};
+use crate::conv::{
+    asn1, classic, compress, data, encoding, float, json, keys, number, pack, proto, saml, serial,
+    symmetric, table, x509, xml, xor, Editor,
+};
 use crate::lazy_regex;
-use flate2::write::DeflateEncoder;
-use flate2::Compression;
-use inflate::InflateWriter;
 use itertools::Itertools;
 use regex::Regex;
 use rustc_serialize::hex::{FromHex, ToHex};
-use sha1::{Digest, Sha1};
 use std::sync::LazyLock;
+use strum::{EnumMessage, VariantArray};
 
 pub fn convert(ui: &mut Ui, editor: &mut Editor) {
+    let error = editor.error.take();
+    output_ui(ui, editor);
+    if editor.error != error {
+        // the editor was laid out before the error was known
+        ui.ctx().request_repaint();
+    }
+}
+
+fn output_ui(ui: &mut Ui, editor: &mut Editor) {
+    if editor.menu.converter == Conv::Data
+        && matches!(editor.menu.data, DataKind::CsvToJson | DataKind::JsonToCsv)
+    {
+        table_ui(ui, editor);
+        return;
+    }
+
+    if editor.menu.converter == Conv::Crypt {
+        if editor.file.is_some() {
+            file_hash_ui(ui, editor);
+            return;
+        }
+        if editor.menu.all_digests {
+            let digests = Digest::VARIANTS
+                .iter()
+                .map(|d| (*d, digest_bytes(*d, editor.code.as_bytes())))
+                .collect::<Vec<_>>();
+            digest_table(ui, editor, &digests);
+            return;
+        }
+    }
+
     let initial_size = vec2(
         ui.available_width(),
         ui.spacing().interact_size.y, // Assume there will be
@@ -34,6 +69,147 @@ pub fn convert(ui: &mut Ui, editor: &mut Editor) {
     });
 }
 
+const TABLE_PREVIEW_ROWS: usize = 200;
+
+/// the parsed table with its CSV to JSON or JSON to CSV conversion
+fn tabulate(menu: &Selected, code: &str) -> Result<(table::Table, String), data::DataError> {
+    let params = &menu.csv;
+    match menu.data {
+        DataKind::JsonToCsv => table::parse_json(code).and_then(|t| {
+            let csv = table::to_csv(&t, params).map_err(|message| data::DataError {
+                message,
+                position: None,
+            })?;
+            Ok((t, csv))
+        }),
+        _ => table::parse_csv(code, params).map(|t| {
+            let json = table::to_json(&t);
+            (t, json)
+        }),
+    }
+}
+
+fn table_ui(ui: &mut Ui, editor: &mut Editor) {
+    let table = match tabulate(&editor.menu, &editor.code) {
+        Ok((table, text)) => {
+            editor.text = text;
+            table
+        },
+        Err(e) => {
+            editor.error = e.position;
+            editor.text = e.message;
+            ui.label(&editor.text);
+            return;
+        },
+    };
+
+    egui::ScrollArea::horizontal()
+        .id_salt("table_preview")
+        .show(ui, |ui| {
+            egui::Grid::new("table_preview_grid")
+                .striped(true)
+                .show(ui, |ui| {
+                    if let Some(headers) = &table.headers {
+                        for h in headers {
+                            ui.label(RichText::new(h).strong());
+                        }
+                        ui.end_row();
+                    }
+                    for row in table.rows.iter().take(TABLE_PREVIEW_ROWS) {
+                        for cell in row {
+                            ui.label(cell);
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+    if table.rows.len() > TABLE_PREVIEW_ROWS {
+        ui.label(
+            RichText::new(format!("… {} more rows", table.rows.len() - TABLE_PREVIEW_ROWS))
+                .small(),
+        );
+    }
+}
+
+fn file_hash_ui(ui: &mut Ui, editor: &mut Editor) {
+    let wanted = editor.menu.digests();
+    let Some(job) = editor.file.as_mut() else {
+        return;
+    };
+    if job.digests != wanted {
+        *job = FileHash::spawn(job.path.clone(), wanted);
+    }
+
+    ui.label(RichText::new(job.path.display().to_string()).small());
+    if job.poll() {
+        ui.add(
+            egui::ProgressBar::new(job.progress())
+                .show_percentage()
+                .animate(true),
+        );
+        ui.horizontal(|ui| {
+            ui.label(format!("{} / {} bytes", job.read(), job.total()));
+            if ui.button("Cancel").clicked() {
+                job.cancel();
+            }
+        });
+        ui.ctx().request_repaint();
+        return;
+    }
+
+    match job.result.clone() {
+        Some(Ok(digests)) => digest_table(ui, editor, &digests),
+        Some(Err(e)) => {
+            editor.text = e;
+            ui.label(&editor.text);
+        },
+        None => {},
+    }
+}
+
+fn digest_table(ui: &mut Ui, editor: &mut Editor, digests: &Digests) {
+    let expected = editor.menu.expected.trim();
+    let rows = digests
+        .iter()
+        .map(|(d, digest)| {
+            let text = format_digest(digest, editor.menu.digest_format);
+            (d.get_message().unwrap(), text, match_digest(digest, expected))
+        })
+        .collect::<Vec<_>>();
+
+    editor.text = rows
+        .iter()
+        .map(|(name, text, _)| format!("{}: {}", name, text))
+        .join("\n");
+
+    egui::Grid::new("digest_table")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            for (name, text, matched) in rows {
+                match matched {
+                    Some(format) => {
+                        let color = ui.visuals().warn_fg_color;
+                        ui.label(RichText::new(name).strong().color(color));
+                        ui.vertical(|ui| {
+                            ui.add(egui::Label::new(RichText::new(text).color(color)).wrap());
+                            ui.label(
+                                RichText::new(format!("✔ {}", format.get_message().unwrap()))
+                                    .small()
+                                    .color(color),
+                            );
+                        });
+                    },
+                    None => {
+                        ui.label(name);
+                        ui.add(egui::Label::new(text).wrap());
+                    },
+                }
+                ui.end_row();
+            }
+        });
+}
+
 lazy_regex!(
     RE_LF:  r"\n",
     RE_PAD: r"=+$",
@@ -43,6 +219,18 @@ lazy_regex!(
     RE_DEC: r"(?<b>\d+)"
 );
 
+/// `0x` byte strings as `FromByteString` reads them, otherwise hex
+fn byte_input(code: &str) -> Result<Vec<u8>, String> {
+    if RE_0X.is_match(code) {
+        let hex = RE_0X
+            .captures_iter(code)
+            .map(|cap| cap["b"].to_owned())
+            .join("");
+        return hex.from_hex().map_err(|e| e.to_string());
+    }
+    encoding::decode(code, Encoding::Hex)
+}
+
 pub fn item_ui(ui: &mut Ui, editor: &mut Editor) {
     let collector = |a: &LazyLock<Regex>, b: &String| {
         a.captures_iter(b)
@@ -83,29 +271,40 @@ pub fn item_ui(ui: &mut Ui, editor: &mut Editor) {
                     };
                 },
                 Base64Kind::ToDeflatedSaml => {
-                    let mut buf = vec![];
-                    {
-                        let mut enc = DeflateEncoder::new(&mut buf, Compression::default());
-                        enc.write_all(editor.code.as_ref()).unwrap();
-                    }
-                    editor.text = general_purpose::STANDARD.encode(&buf);
+                    let level = flate2::Compression::default().level();
+                    let deflated = compress::compress(Codec::Deflate, editor.code.as_ref(), level);
+                    editor.text = match deflated {
+                        Ok(buf) => general_purpose::STANDARD.encode(&buf),
+                        Err(e) => e,
+                    };
                 },
                 Base64Kind::FromDeflatedSaml => {
                     let text = RE_PAD.replace_all(&editor.code, "");
-                    let mut inf = InflateWriter::new(Vec::new());
                     editor.text = match engine::GeneralPurpose::new(
                         &alphabet::STANDARD,
                         general_purpose::NO_PAD,
                     )
                     .decode(&*text)
                     {
-                        Ok(a) => match inf.write(a.as_ref()) {
-                            Err(e) => e.to_string(),
-                            _ => String::from_utf8_lossy(&inf.finish().unwrap()).into_owned(),
+                        Ok(a) => match compress::decompress(Codec::Deflate, &a) {
+                            Ok(buf) => {
+                                let text = String::from_utf8_lossy(&buf).into_owned();
+                                xml::pretty(&text, &Default::default()).unwrap_or(text)
+                            },
+                            Err(e) => e,
                         },
                         Err(e) => e.to_string(),
                     };
                 },
+                Base64Kind::FromSamlRedirect => {
+                    editor.text = saml::decode_redirect(&editor.code).unwrap_or_else(|e| e);
+                },
+                Base64Kind::FromSamlPost => {
+                    editor.text = saml::decode_post(&editor.code).unwrap_or_else(|e| e);
+                },
+                Base64Kind::InspectSaml => {
+                    editor.text = saml::inspect(&editor.code).unwrap_or_else(|e| e);
+                },
             }
         },
         Conv::Binary => {
@@ -194,6 +393,15 @@ pub fn item_ui(ui: &mut Ui, editor: &mut Editor) {
                 EscapeKind::FromHtmlSanitise => {
                     editor.text = html_escape::decode_html_entities(&editor.code).into();
                 },
+                EscapeKind::ToJsonString => {
+                    editor.text = json::escape(&editor.code);
+                },
+                EscapeKind::FromJsonString => {
+                    editor.text = json::unescape(&editor.code).unwrap_or_else(|e| e.to_string());
+                },
+                EscapeKind::UnwrapJsonString => {
+                    editor.text = json::unwrap(&editor.code);
+                },
                 EscapeKind::ToUtf7 => {
                     // rfc 3501
                     editor.text = utf7_imap::encode_utf7_imap(editor.code.to_string());
@@ -204,33 +412,177 @@ pub fn item_ui(ui: &mut Ui, editor: &mut Editor) {
             }
         },
 
-        Conv::Crypt => match editor.menu.digest {
-            enum_variants::Digest::Md5 => {
-                let digest = md5::compute(&editor.code);
-                editor.text = digest.to_hex();
-            },
-            enum_variants::Digest::Sha1 => {
-                let mut h = Sha1::new();
-                sha1::Digest::update(&mut h, <str as AsRef<[u8]>>::as_ref(&editor.code));
-                editor.text = String::from_utf8_lossy((*h.finalize().to_hex()).as_ref()).into();
-            },
-            enum_variants::Digest::Sha224 => {
-                editor.text = hasher("sha224", &editor.code);
-            },
-            enum_variants::Digest::Sha256 => {
-                editor.text = hasher("sha256", &editor.code);
-            },
-            enum_variants::Digest::Sha384 => {
-                editor.text = hasher("sha384", &editor.code);
-            },
-            enum_variants::Digest::Sha512 => {
-                editor.text = hasher("sha512", &editor.code);
-            },
+        Conv::Crypt => {
+            let digest = digest_bytes(editor.menu.digest, editor.code.as_bytes());
+            editor.text = format_digest(&digest, editor.menu.digest_format);
+        },
+        Conv::Encrypt => {
+            editor.text = crypt_symmetric(&editor.menu, &editor.code).unwrap_or_else(|e| e);
+        },
+        Conv::Cipher => {
+            let m = &editor.menu;
+            editor.text = match m.classic {
+                ClassicCipher::Rot13 => classic::rot13(&editor.code),
+                ClassicCipher::Rot47 => classic::rot47(&editor.code),
+                ClassicCipher::CaesarEncode => classic::caesar(&editor.code, m.shift),
+                ClassicCipher::CaesarDecode => classic::caesar(&editor.code, -m.shift),
+                ClassicCipher::CaesarBruteForce => classic::caesar_brute_force(&editor.code),
+                ClassicCipher::VigenereEncode => {
+                    classic::vigenere(&editor.code, &m.classic_key, false).unwrap_or_else(|e| e)
+                },
+                ClassicCipher::VigenereDecode => {
+                    classic::vigenere(&editor.code, &m.classic_key, true).unwrap_or_else(|e| e)
+                },
+                ClassicCipher::Atbash => classic::atbash(&editor.code),
+                ClassicCipher::AffineEncode => {
+                    classic::affine(&editor.code, &m.affine, false).unwrap_or_else(|e| e)
+                },
+                ClassicCipher::AffineDecode => {
+                    classic::affine(&editor.code, &m.affine, true).unwrap_or_else(|e| e)
+                },
+            };
+        },
+        Conv::Xor => {
+            editor.text = crypt_xor(&editor.menu, &editor.code).unwrap_or_else(|e| e);
+        },
+        Conv::Compression => {
+            editor.text = compression(&editor.menu, &editor.code).unwrap_or_else(|e| e);
+        },
+        Conv::Data => {
+            let m = &editor.menu;
+            let result: Result<_, data::DataError> = match m.data {
+                DataKind::JsonPretty => json::pretty(&editor.code, &m.json).map_err(Into::into),
+                DataKind::JsonMinify => json::minify(&editor.code, &m.json).map_err(Into::into),
+                DataKind::JsonValidate => json::validate(&editor.code).map_err(Into::into),
+                DataKind::Convert => data::convert(&editor.code, m.data_from, m.data_to),
+                DataKind::XmlPretty => xml::pretty(&editor.code, &m.xml),
+                DataKind::XmlMinify => xml::minify(&editor.code),
+                DataKind::XmlC14n => xml::exc_c14n(&editor.code),
+                DataKind::BinaryToJson => encoding::decode(&editor.code, m.binary_encoding)
+                    .and_then(|b| serial::decode(m.binary_format, &b))
+                    .map_err(|message| data::DataError {
+                        message,
+                        position: None,
+                    }),
+                DataKind::JsonToBinary => serial::encode(m.binary_format, &editor.code)
+                    .map(|b| encoding::encode(&b, m.binary_encoding)),
+                DataKind::Protobuf => encoding::decode(&editor.code, m.binary_encoding)
+                    .and_then(|b| proto::decode(&b, m.proto.as_ref(), &m.proto_message))
+                    .map_err(|message| data::DataError {
+                        message,
+                        position: None,
+                    }),
+                // previewed as a table by table_ui
+                DataKind::CsvToJson | DataKind::JsonToCsv => {
+                    tabulate(m, &editor.code).map(|(_, text)| text)
+                },
+            };
+            editor.text = match result {
+                Ok(text) => text,
+                Err(e) => {
+                    editor.error = e.position;
+                    e.message
+                },
+            };
+        },
+        Conv::Asn1 => {
+            editor.text = match editor.menu.asn1 {
+                Asn1Kind::Dump => asn1::dump(&editor.code),
+                Asn1Kind::Certificate => x509::inspect(&editor.code),
+                Asn1Kind::KeyConvert => keys::convert(&editor.code, editor.menu.key_format),
+            }
+            .unwrap_or_else(|e| e);
+        },
+        Conv::Number => {
+            editor.text = match editor.menu.number {
+                NumberKind::Radix => number::radix(&editor.code, &editor.menu.radix),
+                NumberKind::Float => float::inspect(&editor.code, editor.menu.float_format),
+                NumberKind::Unpack => byte_input(&editor.code)
+                    .and_then(|b| pack::unpack(&editor.menu.struct_format, &b)),
+                NumberKind::Pack => {
+                    pack::pack(&editor.menu.struct_format, &editor.code).map(|b| b.to_hex())
+                },
+            }
+            .unwrap_or_else(|e| e);
+        },
+        Conv::Kdf => {
+            let output = editor.kdf.memorise(
+                editor.menu.kdf,
+                &editor.menu.kdf_params,
+                &editor.code,
+                &editor.menu.kdf_hash,
+            );
+            let Some(output) = output else {
+                editor.text.clear();
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("hashing…");
+                    if ui.button("Cancel").clicked() {
+                        editor.kdf.cancel();
+                    }
+                });
+                ui.ctx().request_repaint();
+                return;
+            };
+            if editor.kdf.busy() {
+                // a cancelled worker still runs, keep polling for its result
+                ui.ctx().request_repaint();
+            }
+            editor.text = output;
         },
     }
     ui.label(&editor.text);
 }
 
+fn crypt_symmetric(menu: &Selected, code: &str) -> Result<String, String> {
+    let (key, iv, aad) = (menu.key.bytes()?, menu.iv.bytes()?, menu.aad.bytes()?);
+    let params = symmetric::Params {
+        key: &key,
+        iv: &iv,
+        aad: &aad,
+    };
+    match menu.direction {
+        Direction::Encrypt => {
+            symmetric::encrypt(menu.cipher, menu.block_mode, &params, code.as_bytes())
+                .map(|c| encoding::encode(&c, menu.data_encoding))
+        },
+        Direction::Decrypt => {
+            let data = encoding::decode(code, menu.data_encoding)?;
+            symmetric::decrypt(menu.cipher, menu.block_mode, &params, &data)
+                .map(|p| String::from_utf8_lossy(&p).into_owned())
+        },
+    }
+}
+
+fn crypt_xor(menu: &Selected, code: &str) -> Result<String, String> {
+    let data = encoding::decode(code, menu.xor_input)?;
+    match menu.xor {
+        XorKind::WithKey => {
+            xor::xor(&data, &menu.xor_key.bytes()?).map(|x| encoding::encode(&x, menu.xor_output))
+        },
+        XorKind::BruteForce => Ok(xor::brute_force(&data)),
+    }
+}
+
+fn compression(menu: &Selected, code: &str) -> Result<String, String> {
+    let params = &menu.compress;
+    match menu.compress_mode {
+        CompressMode::Compress => {
+            let data = encoding::decode(code, params.plain)?;
+            compress::compress(menu.codec, &data, params.level)
+                .map(|x| encoding::encode(&x, params.packed))
+        },
+        CompressMode::Decompress => {
+            let data = encoding::decode(code, params.packed)?;
+            compress::decompress(menu.codec, &data).map(|x| encoding::encode(&x, params.plain))
+        },
+        CompressMode::Header => match menu.codec {
+            Codec::Gzip => compress::gzip_header(&encoding::decode(code, params.packed)?),
+            _ => Err("only gzip streams carry a header".to_owned()),
+        },
+    }
+}
+
 const TR_SAFE_URL: [char; 4] = ['/', '+', '_', '-'];
 
 #[inline]
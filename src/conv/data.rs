@@ -0,0 +1,186 @@
+use crate::conv::enum_variants::DataFormat;
+use serde_json::{Map, Number, Value};
+
+/// error with an optional 1-based (line, column) input position
+pub struct DataError {
+    pub message: String,
+    pub position: Option<(usize, usize)>,
+}
+
+impl DataError {
+    fn new(message: String) -> Self {
+        Self {
+            message,
+            position: None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for DataError {
+    fn from(e: serde_json::Error) -> Self {
+        Self {
+            message: e.to_string(),
+            position: Some((e.line(), e.column())),
+        }
+    }
+}
+
+impl From<serde_yaml::Error> for DataError {
+    fn from(e: serde_yaml::Error) -> Self {
+        Self {
+            message: e.to_string(),
+            position: e.location().map(|l| (l.line(), l.column())),
+        }
+    }
+}
+
+pub fn convert(text: &str, from: DataFormat, to: DataFormat) -> Result<String, DataError> {
+    let value = match from {
+        DataFormat::Json => serde_json::from_str::<Value>(text)?,
+        DataFormat::Yaml => yaml_to_json(serde_yaml::from_str(text)?, "$")?,
+        DataFormat::Toml => match text.parse::<toml::Table>() {
+            Ok(table) => toml_to_json(toml::Value::Table(table), "$")?,
+            Err(e) => {
+                return Err(DataError {
+                    message: e.message().to_owned(),
+                    position: e.span().map(|r| line_column(text, r.start)),
+                })
+            },
+        },
+    };
+    match to {
+        DataFormat::Json => Ok(serde_json::to_string_pretty(&value)?),
+        DataFormat::Yaml => Ok(serde_yaml::to_string(&value)?),
+        DataFormat::Toml => match json_to_toml(value, "$")? {
+            toml::Value::Table(table) => {
+                toml::to_string_pretty(&table).map_err(|e| DataError::new(e.to_string()))
+            },
+            _ => Err(DataError::new("TOML document root must be a table".to_owned())),
+        },
+    }
+}
+
+fn yaml_to_json(value: serde_yaml::Value, path: &str) -> Result<Value, DataError> {
+    use serde_yaml::Value as Yaml;
+    Ok(match value {
+        Yaml::Null => Value::Null,
+        Yaml::Bool(b) => Value::Bool(b),
+        Yaml::Number(n) => match (n.as_i64(), n.as_u64(), n.as_f64()) {
+            (Some(i), _, _) => Value::from(i),
+            (_, Some(u), _) => Value::from(u),
+            (_, _, f) => Value::Number(f.and_then(Number::from_f64).ok_or_else(|| {
+                DataError::new(format!("{}: {} is not representable in JSON", path, n))
+            })?),
+        },
+        Yaml::String(s) => Value::String(s),
+        Yaml::Sequence(seq) => Value::Array(
+            seq.into_iter()
+                .enumerate()
+                .map(|(i, v)| yaml_to_json(v, &format!("{}[{}]", path, i)))
+                .collect::<Result<_, _>>()?,
+        ),
+        Yaml::Mapping(mapping) => {
+            let mut map = Map::new();
+            for (k, v) in mapping {
+                // scalar keys become strings, others have no JSON equivalent
+                let key = match k {
+                    Yaml::String(s) => s,
+                    Yaml::Bool(b) => b.to_string(),
+                    Yaml::Number(n) => n.to_string(),
+                    k => {
+                        return Err(DataError::new(format!(
+                            "{}: mapping key {:?} is not representable as a JSON key",
+                            path, k
+                        )))
+                    },
+                };
+                let v = yaml_to_json(v, &format!("{}.{}", path, key))?;
+                map.insert(key, v);
+            }
+            Value::Object(map)
+        },
+        Yaml::Tagged(tagged) => {
+            return Err(DataError::new(format!(
+                "{}: tagged value {} is not representable in JSON",
+                path, tagged.tag
+            )))
+        },
+    })
+}
+
+/// datetimes become `{"$datetime": "1979-05-27T07:32:00Z"}` so they convert back to TOML
+/// datetimes and not to strings
+fn toml_to_json(value: toml::Value, path: &str) -> Result<Value, DataError> {
+    Ok(match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => Value::Number(Number::from_f64(f).ok_or_else(|| {
+            DataError::new(format!("{}: {} is not representable in JSON", path, f))
+        })?),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(d) => serde_json::json!({ "$datetime": d.to_string() }),
+        toml::Value::Array(a) => Value::Array(
+            a.into_iter()
+                .enumerate()
+                .map(|(i, v)| toml_to_json(v, &format!("{}[{}]", path, i)))
+                .collect::<Result<_, _>>()?,
+        ),
+        toml::Value::Table(t) => Value::Object(
+            t.into_iter()
+                .map(|(k, v)| {
+                    let v = toml_to_json(v, &format!("{}.{}", path, k))?;
+                    Ok((k, v))
+                })
+                .collect::<Result<_, DataError>>()?,
+        ),
+    })
+}
+
+fn json_to_toml(value: Value, path: &str) -> Result<toml::Value, DataError> {
+    Ok(match value {
+        Value::Null => {
+            return Err(DataError::new(format!("{}: null is not representable in TOML", path)))
+        },
+        Value::Bool(b) => toml::Value::Boolean(b),
+        Value::Number(n) => match (n.as_i64(), n.as_f64()) {
+            (Some(i), _) => toml::Value::Integer(i),
+            (None, _) if n.is_u64() => {
+                return Err(DataError::new(format!(
+                    "{}: {} is out of range for a TOML integer",
+                    path, n
+                )))
+            },
+            (None, f) => toml::Value::Float(f.ok_or_else(|| {
+                DataError::new(format!("{}: {} is not representable in TOML", path, n))
+            })?),
+        },
+        Value::String(s) => toml::Value::String(s),
+        Value::Object(o) if o.len() == 1 && o.contains_key("$datetime") => {
+            let datetime = o["$datetime"].as_str().and_then(|d| d.parse().ok());
+            toml::Value::Datetime(datetime.ok_or_else(|| {
+                DataError::new(format!("{}: $datetime must be a TOML datetime string", path))
+            })?)
+        },
+        Value::Array(a) => toml::Value::Array(
+            a.into_iter()
+                .enumerate()
+                .map(|(i, v)| json_to_toml(v, &format!("{}[{}]", path, i)))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(o) => toml::Value::Table(
+            o.into_iter()
+                .map(|(k, v)| {
+                    let v = json_to_toml(v, &format!("{}.{}", path, k))?;
+                    Ok((k, v))
+                })
+                .collect::<Result<_, DataError>>()?,
+        ),
+    })
+}
+
+/// 1-based (line, column) of a byte offset
+pub fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (before.matches('\n').count() + 1, before.len() - line_start + 1)
+}
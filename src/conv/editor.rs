@@ -1,5 +1,18 @@
-
-use crate::conv::enum_variants::{Base64Kind, BinaryKind, Conv, Digest, EscapeKind};
+use crate::conv::classic::{AffineKey, AFFINE_A};
+use crate::conv::compress::{self, CompressParams};
+use crate::conv::encoding::Field;
+use crate::conv::enum_variants::{
+    Asn1Kind, Base64Kind, BinaryFormat, BinaryKind, BlockMode, ClassicCipher, Codec, CompressMode,
+    Conv, DataFormat, DataKind, Delimiter, Digest, DigestFormat, Direction, Encoding, EscapeKind,
+    FloatFormat, KdfKind, KeyFormat, NumberKind, Quoting, SymmetricCipher, XorKind,
+};
+use crate::conv::file_hash::FileHash;
+use crate::conv::json::JsonParams;
+use crate::conv::kdf::{KdfCache, KdfParams};
+use crate::conv::number::RadixParams;
+use crate::conv::proto::Schema;
+use crate::conv::table::CsvParams;
+use crate::conv::xml::XmlParams;
 use eframe::egui;
 use eframe::egui::SizeHint::Size;
 use eframe::egui::{Align, Image, Response, ScrollArea, Sense, Ui};
@@ -12,6 +25,10 @@ pub struct Editor {
     pub code: String,
     pub menu: Selected,
     pub text: String,
+    pub file: Option<FileHash>,
+    pub kdf: KdfCache,
+    /// 1-based (line, column) of an input error, marked in the editor
+    pub error: Option<(usize, usize)>,
     cache: crate::conv::LayoutCache,
 }
 
@@ -19,9 +36,58 @@ pub struct Editor {
 pub struct Selected {
     pub converter: Conv,
     pub digest: Digest,
+    pub digest_format: DigestFormat,
+    pub all_digests: bool,
+    pub expected: String,
     pub base64: Base64Kind,
     pub binary: BinaryKind,
     pub escape: EscapeKind,
+    pub kdf: KdfKind,
+    pub kdf_params: KdfParams,
+    pub kdf_hash: String,
+    pub cipher: SymmetricCipher,
+    pub block_mode: BlockMode,
+    pub direction: Direction,
+    pub key: Field,
+    pub iv: Field,
+    pub aad: Field,
+    pub data_encoding: Encoding,
+    pub classic: ClassicCipher,
+    pub shift: i32,
+    pub classic_key: String,
+    pub affine: AffineKey,
+    pub xor: XorKind,
+    pub xor_key: Field,
+    pub xor_input: Encoding,
+    pub xor_output: Encoding,
+    pub codec: Codec,
+    pub compress_mode: CompressMode,
+    pub compress: CompressParams,
+    pub data: DataKind,
+    pub json: JsonParams,
+    pub xml: XmlParams,
+    pub data_from: DataFormat,
+    pub data_to: DataFormat,
+    pub csv: CsvParams,
+    pub binary_format: BinaryFormat,
+    pub binary_encoding: Encoding,
+    pub proto: Option<Result<Schema, String>>,
+    pub proto_message: String,
+    pub asn1: Asn1Kind,
+    pub key_format: KeyFormat,
+    pub number: NumberKind,
+    pub radix: RadixParams,
+    pub float_format: FloatFormat,
+    pub struct_format: String,
+}
+
+impl Selected {
+    pub fn digests(&self) -> Vec<Digest> {
+        if self.all_digests {
+            return Digest::VARIANTS.to_vec();
+        }
+        vec![self.digest]
+    }
 }
 
 impl PartialEq for Editor {
@@ -32,20 +98,51 @@ impl PartialEq for Editor {
 
 impl Editor {
     pub fn panels(&mut self, ctx: &egui::Context) {
+        if self.menu.converter == Conv::Crypt {
+            let dropped = ctx.input(|i| i.raw.dropped_files.first().and_then(|f| f.path.clone()));
+            if let Some(path) = dropped {
+                self.file = Some(FileHash::spawn(path, self.menu.digests()));
+            }
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             self.ui(ui);
         });
     }
 
     pub fn ui(&mut self, ui: &mut egui::Ui) {
-        let Self { menu, .. } = self;
+        let Self { menu, file, .. } = self;
 
         ui.horizontal(|ui| {
             combobox::<Conv>(ui, "converter", &mut menu.converter);
 
             match menu.converter {
                 Conv::Crypt => {
-                    combobox::<Digest>(ui, "crypt", &mut menu.digest);
+                    ui.toggle_value(&mut menu.all_digests, "All")
+                        .on_hover_text("compute every digest and compare with an expected value");
+                    if !menu.all_digests {
+                        combobox::<Digest>(ui, "crypt", &mut menu.digest);
+                    }
+                    combobox::<DigestFormat>(ui, "digest_format", &mut menu.digest_format);
+                    if menu.all_digests {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut menu.expected)
+                                .hint_text("expected")
+                                .desired_width(120.0),
+                        );
+                    }
+                    if ui
+                        .button("📂")
+                        .on_hover_text("hash a file (or drop one here)")
+                        .clicked()
+                    {
+                        if let Some(path) = rfd::FileDialog::new().pick_file() {
+                            *file = Some(FileHash::spawn(path, menu.digests()));
+                        }
+                    }
+                    if file.is_some() && ui.button("✖").on_hover_text("close file").clicked() {
+                        *file = None;
+                    }
                 },
                 Conv::Base64 => {
                     combobox::<Base64Kind>(ui, "base64", &mut menu.base64);
@@ -56,6 +153,78 @@ impl Editor {
                 Conv::Escape => {
                     combobox::<EscapeKind>(ui, "escape", &mut menu.escape);
                 },
+                Conv::Kdf => {
+                    combobox::<KdfKind>(ui, "kdf", &mut menu.kdf);
+                },
+                Conv::Encrypt => {
+                    combobox::<SymmetricCipher>(ui, "cipher", &mut menu.cipher);
+                    if menu.cipher != SymmetricCipher::ChaCha20Poly1305 {
+                        combobox::<BlockMode>(ui, "block_mode", &mut menu.block_mode);
+                    }
+                    combobox::<Direction>(ui, "direction", &mut menu.direction);
+                },
+                Conv::Cipher => {
+                    combobox::<ClassicCipher>(ui, "classic", &mut menu.classic);
+                    classic_params_ui(ui, menu);
+                },
+                Conv::Xor => {
+                    combobox::<XorKind>(ui, "xor", &mut menu.xor);
+                },
+                Conv::Compression => {
+                    combobox::<Codec>(ui, "codec", &mut menu.codec);
+                    combobox::<CompressMode>(ui, "compress_mode", &mut menu.compress_mode);
+                },
+                Conv::Data => {
+                    combobox::<DataKind>(ui, "data", &mut menu.data);
+                    if menu.data == DataKind::JsonPretty {
+                        ui.label("indent");
+                        ui.add(egui::DragValue::new(&mut menu.json.indent).range(0..=8));
+                    }
+                    if menu.data == DataKind::XmlPretty {
+                        ui.label("indent");
+                        ui.add(egui::DragValue::new(&mut menu.xml.indent).range(0..=8));
+                    }
+                    if menu.data == DataKind::Convert {
+                        combobox::<DataFormat>(ui, "data_from", &mut menu.data_from);
+                        ui.label("→");
+                        combobox::<DataFormat>(ui, "data_to", &mut menu.data_to);
+                    } else if matches!(menu.data, DataKind::JsonPretty | DataKind::JsonMinify) {
+                        ui.checkbox(&mut menu.json.sort_keys, "sort keys");
+                    } else if matches!(menu.data, DataKind::BinaryToJson | DataKind::JsonToBinary)
+                    {
+                        combobox::<BinaryFormat>(ui, "binary_format", &mut menu.binary_format);
+                        combobox::<Encoding>(ui, "binary_encoding", &mut menu.binary_encoding);
+                    }
+                    if menu.data == DataKind::Protobuf {
+                        combobox::<Encoding>(ui, "proto_encoding", &mut menu.binary_encoding);
+                    }
+                },
+                Conv::Asn1 => {
+                    combobox::<Asn1Kind>(ui, "asn1", &mut menu.asn1);
+                    if menu.asn1 == Asn1Kind::KeyConvert {
+                        ui.label("→");
+                        combobox::<KeyFormat>(ui, "key_format", &mut menu.key_format);
+                    }
+                },
+                Conv::Number => {
+                    combobox::<NumberKind>(ui, "number", &mut menu.number);
+                    if menu.number == NumberKind::Radix {
+                        ui.label("base");
+                        ui.add(egui::DragValue::new(&mut menu.radix.from).range(2..=36));
+                        ui.label("→");
+                        ui.add(egui::DragValue::new(&mut menu.radix.to).range(2..=36));
+                    }
+                    if menu.number == NumberKind::Float {
+                        combobox::<FloatFormat>(ui, "float_format", &mut menu.float_format);
+                    }
+                    if matches!(menu.number, NumberKind::Unpack | NumberKind::Pack) {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut menu.struct_format)
+                                .hint_text("<IHhQ")
+                                .desired_width(120.0),
+                        );
+                    }
+                },
             }
 
             ui.with_layout(egui::Layout::right_to_left(Align::RIGHT), |ui| {
@@ -68,6 +237,88 @@ impl Editor {
             })
         });
 
+        if menu.converter == Conv::Kdf {
+            ui.horizontal(|ui| kdf_params_ui(ui, menu));
+        }
+        if menu.converter == Conv::Xor {
+            ui.horizontal(|ui| {
+                if menu.xor == XorKind::WithKey {
+                    field_ui(ui, "xor key", &mut menu.xor_key);
+                }
+                ui.label("input");
+                combobox::<Encoding>(ui, "xor_input", &mut menu.xor_input);
+                if menu.xor == XorKind::WithKey {
+                    ui.label("output");
+                    combobox::<Encoding>(ui, "xor_output", &mut menu.xor_output);
+                }
+            });
+        }
+        if menu.converter == Conv::Compression {
+            ui.horizontal(|ui| {
+                let params = &mut menu.compress;
+                if menu.compress_mode == CompressMode::Compress {
+                    if let Some(range) = compress::level_range(menu.codec) {
+                        ui.label("level");
+                        ui.add(egui::DragValue::new(&mut params.level).range(range));
+                    }
+                }
+                ui.label("plain");
+                combobox::<Encoding>(ui, "compress_plain", &mut params.plain);
+                ui.label("compressed");
+                combobox::<Encoding>(ui, "compress_packed", &mut params.packed);
+            });
+        }
+        if menu.converter == Conv::Data
+            && matches!(menu.data, DataKind::CsvToJson | DataKind::JsonToCsv)
+        {
+            ui.horizontal(|ui| {
+                ui.label("delimiter");
+                combobox::<Delimiter>(ui, "csv_delimiter", &mut menu.csv.delimiter);
+                ui.checkbox(&mut menu.csv.header, "header row");
+                if menu.data == DataKind::JsonToCsv {
+                    combobox::<Quoting>(ui, "csv_quoting", &mut menu.csv.quoting);
+                }
+            });
+        }
+        if menu.converter == Conv::Data && menu.data == DataKind::Protobuf {
+            ui.horizontal(|ui| {
+                let open = ui.button("📂 .proto").on_hover_text("name fields from a .proto file");
+                if open.clicked() {
+                    let dialog = rfd::FileDialog::new().add_filter("proto", &["proto"]);
+                    if let Some(path) = dialog.pick_file() {
+                        menu.proto = Some(Schema::load(&path));
+                    }
+                }
+                if let Some(Ok(schema)) = &menu.proto {
+                    ui.label(&schema.name);
+                }
+                if menu.proto.is_some() {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut menu.proto_message)
+                            .hint_text("message")
+                            .desired_width(160.0),
+                    );
+                    if ui.button("✖").on_hover_text("decode without a schema").clicked() {
+                        menu.proto = None;
+                    }
+                }
+            });
+        }
+        if menu.converter == Conv::Encrypt {
+            ui.horizontal(|ui| {
+                field_ui(ui, "key", &mut menu.key);
+                field_ui(ui, "iv / nonce", &mut menu.iv);
+            });
+            ui.horizontal(|ui| {
+                field_ui(ui, "aad", &mut menu.aad);
+                ui.label(match menu.direction {
+                    Direction::Encrypt => "output",
+                    Direction::Decrypt => "input",
+                });
+                combobox::<Encoding>(ui, "data_encoding", &mut menu.data_encoding);
+            });
+        }
+
         ui.separator();
 
         ui.columns(2, |columns| {
@@ -84,10 +335,12 @@ impl Editor {
     }
 
     fn editor_ui(&mut self, ui: &mut egui::Ui) {
-        let Self { code, cache, .. } = self;
+        let Self {
+            code, cache, error, ..
+        } = self;
 
         let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
-            let mut layout_job = cache.memorise(ui.style(), text);
+            let mut layout_job = cache.memorise(ui.style(), text, *error);
             layout_job.wrap.max_width = wrap_width;
             ui.fonts(|f| f.layout_job(layout_job))
         };
@@ -119,6 +372,91 @@ where
         });
 }
 
+fn field_ui(ui: &mut Ui, hint: &str, field: &mut Field) {
+    ui.add(
+        egui::TextEdit::singleline(&mut field.text)
+            .hint_text(hint)
+            .desired_width(150.0),
+    );
+    combobox::<Encoding>(ui, hint, &mut field.encoding);
+}
+
+fn classic_params_ui(ui: &mut Ui, menu: &mut Selected) {
+    match menu.classic {
+        ClassicCipher::CaesarEncode | ClassicCipher::CaesarDecode => {
+            ui.label("shift");
+            ui.add(egui::DragValue::new(&mut menu.shift).range(0..=25));
+        },
+        ClassicCipher::VigenereEncode | ClassicCipher::VigenereDecode => {
+            ui.add(
+                egui::TextEdit::singleline(&mut menu.classic_key)
+                    .hint_text("key")
+                    .desired_width(100.0),
+            );
+        },
+        ClassicCipher::AffineEncode | ClassicCipher::AffineDecode => {
+            ui.label("a");
+            egui::ComboBox::from_id_salt("affine_a")
+                .selected_text(menu.affine.a.to_string())
+                .width(40.0)
+                .show_ui(ui, |ui| {
+                    for a in AFFINE_A {
+                        ui.selectable_value(&mut menu.affine.a, a, a.to_string());
+                    }
+                });
+            ui.label("b");
+            ui.add(egui::DragValue::new(&mut menu.affine.b).range(0..=25));
+        },
+        _ => {},
+    }
+}
+
+fn kdf_params_ui(ui: &mut Ui, menu: &mut Selected) {
+    let params = &mut menu.kdf_params;
+    match menu.kdf {
+        KdfKind::Bcrypt => {
+            ui.label("cost");
+            ui.add(egui::DragValue::new(&mut params.bcrypt_cost).range(4..=31));
+        },
+        KdfKind::Scrypt => {
+            ui.label("log N");
+            ui.add(egui::DragValue::new(&mut params.scrypt_log_n).range(1..=20));
+            ui.label("r");
+            ui.add(egui::DragValue::new(&mut params.scrypt_r).range(1..=16));
+            ui.label("p");
+            ui.add(egui::DragValue::new(&mut params.scrypt_p).range(1..=16));
+        },
+        KdfKind::Argon2id => {
+            ui.label("m KiB");
+            ui.add(egui::DragValue::new(&mut params.argon2_m_cost).range(8..=1 << 20));
+            ui.label("t");
+            ui.add(egui::DragValue::new(&mut params.argon2_t_cost).range(1..=100));
+            ui.label("p");
+            ui.add(egui::DragValue::new(&mut params.argon2_p_cost).range(1..=16));
+        },
+        KdfKind::Pbkdf2Sha1 | KdfKind::Pbkdf2Sha256 | KdfKind::Pbkdf2Sha512 => {
+            ui.label("rounds");
+            ui.add(egui::DragValue::new(&mut params.pbkdf2_rounds).range(1..=10_000_000));
+        },
+        KdfKind::Md5Crypt | KdfKind::Apr1 | KdfKind::Sha256Crypt | KdfKind::Sha512Crypt => {
+            if matches!(menu.kdf, KdfKind::Sha256Crypt | KdfKind::Sha512Crypt) {
+                ui.label("rounds");
+                ui.add(egui::DragValue::new(&mut params.sha_crypt_rounds).range(1000..=5_000_000));
+            }
+            ui.add(
+                egui::TextEdit::singleline(&mut params.user)
+                    .hint_text("user")
+                    .desired_width(60.0),
+            );
+        },
+    }
+    ui.add(
+        egui::TextEdit::singleline(&mut menu.kdf_hash)
+            .hint_text("hash to verify")
+            .desired_width(f32::INFINITY),
+    );
+}
+
 struct LoadIcon {
     texture: Option<egui::TextureHandle>,
 }
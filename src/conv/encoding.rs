@@ -0,0 +1,54 @@
+use crate::conv::enum_variants::Encoding;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig, STANDARD};
+use base64::engine::DecodePaddingMode;
+use base64::{alphabet, Engine as _};
+use rustc_serialize::hex::{FromHex, ToHex};
+
+/// accepts both standard and url safe alphabets, with or without padding
+const BASE64_LENIENT: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// a toolbar input whose bytes are given as hex, base64 or plain text
+#[derive(Default, Clone, PartialEq)]
+pub struct Field {
+    pub text: String,
+    pub encoding: Encoding,
+}
+
+impl Field {
+    pub fn bytes(&self) -> Result<Vec<u8>, String> { decode(&self.text, self.encoding) }
+}
+
+pub fn decode(text: &str, encoding: Encoding) -> Result<Vec<u8>, String> {
+    match encoding {
+        Encoding::Hex => text
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != ':')
+            .collect::<String>()
+            .from_hex()
+            .map_err(|e| e.to_string()),
+        Encoding::Base64 => {
+            let text = text
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(|c| match c {
+                    '-' => '+',
+                    '_' => '/',
+                    c => c,
+                })
+                .collect::<String>();
+            BASE64_LENIENT.decode(text).map_err(|e| e.to_string())
+        },
+        Encoding::Text => Ok(text.as_bytes().to_vec()),
+    }
+}
+
+pub fn encode(bytes: &[u8], encoding: Encoding) -> String {
+    match encoding {
+        Encoding::Hex => bytes.to_hex(),
+        Encoding::Base64 => STANDARD.encode(bytes),
+        Encoding::Text => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
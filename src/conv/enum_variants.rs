@@ -16,6 +16,30 @@ pub enum Conv {
     /// Crypt
     #[strum(message = "Crypt           ▸")]
     Crypt,
+    /// password hashing (key derivation functions)
+    #[strum(message = "KDF             ▸")]
+    Kdf,
+    /// symmetric encryption / decryption
+    #[strum(message = "Encrypt/Decrypt ▸")]
+    Encrypt,
+    /// classical ciphers
+    #[strum(message = "Cipher          ▸")]
+    Cipher,
+    /// xor with a repeating key
+    #[strum(message = "XOR             ▸")]
+    Xor,
+    /// compression
+    #[strum(message = "Compression     ▸")]
+    Compression,
+    /// structured data formats
+    #[strum(message = "Data            ▸")]
+    Data,
+    /// ASN.1, certificates and keys
+    #[strum(message = "ASN.1 / PKI     ▸")]
+    Asn1,
+    /// numbers: bases, floats and packed integers
+    #[strum(message = "Number          ▸")]
+    Number,
 }
 
 #[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
@@ -42,6 +66,36 @@ pub enum Digest {
     Sha512,
 }
 
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum DigestFormat {
+    #[default]
+    /// lowercase hex
+    /// ex: 'd41d8cd98f00b204e9800998ecf8427e'
+    #[strum(message = "hex")]
+    LowerHex,
+    /// uppercase hex
+    /// ex: 'D41D8CD98F00B204E9800998ECF8427E'
+    #[strum(message = "HEX")]
+    UpperHex,
+    /// colon separated fingerprint (certificates)
+    /// ex: 'D4:1D:8C:D9:8F:00:B2:04:E9:80:09:98:EC:F8:42:7E'
+    #[strum(message = "Fingerprint")]
+    Fingerprint,
+    /// base64 (rfc 4648, SRI, Content-MD5)
+    /// ex: '1B2M2Y8AsgTpgAmY7PhCfg=='
+    #[strum(message = "Base64")]
+    Base64,
+    /// base64 url (rfc 4648 url safe)
+    /// ex: '1B2M2Y8AsgTpgAmY7PhCfg'
+    #[strum(message = "Base64URL")]
+    Base64Url,
+    /// byte string
+    /// ex: '0xd4, 0x1d, 0x8c, 0xd9, ...'
+    #[strum(message = "Byte string")]
+    ByteString,
+}
+
 #[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
 #[strum(serialize_all = "kebab-case")]
 pub enum Base64Kind {
@@ -61,6 +115,18 @@ pub enum Base64Kind {
     /// from deflated saml auth
     #[strum(message = "From Deflated Saml")]
     FromDeflatedSaml,
+    /// SAML HTTP-Redirect binding (url decode, base64, inflate)
+    /// ex: 'https://idp/sso?SAMLRequest=fZJ...&RelayState=...&SigAlg=...'
+    #[strum(message = "From Saml Redirect")]
+    FromSamlRedirect,
+    /// SAML HTTP-POST binding (url decode, base64)
+    /// ex: 'SAMLResponse=PHNhbWxw...&RelayState=...'
+    #[strum(message = "From Saml Post")]
+    FromSamlPost,
+    /// SAML message summary: issuer, NameID, audience, validity, attributes, signatures
+    /// accepts xml, a redirect url or a post form body
+    #[strum(message = "Inspect Saml")]
+    InspectSaml,
 }
 
 #[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
@@ -129,6 +195,18 @@ pub enum EscapeKind {
     /// ex: '&#9787;' = '☻'
     #[strum(message = "From Html Sanitise")]
     FromHtmlSanitise,
+    /// To JSON string (rfc 8259), non ascii as \uXXXX
+    /// ex: '𝕊"a' = '\ud835\udd4a\"a'
+    #[strum(message = "To JSON String")]
+    ToJsonString,
+    /// From JSON string, surrounding quotes are optional
+    /// ex: '\ud835\udd4a\"a' = '𝕊"a'
+    #[strum(message = "From JSON String")]
+    FromJsonString,
+    /// Unwrap JSON encoded into JSON strings, also inside log lines
+    /// ex: '"{\"a\":\"[1]\"}"' = '{"a": [1]}'
+    #[strum(message = "Unwrap JSON String")]
+    UnwrapJsonString,
     /// to utf-7 (rfc 3501)
     #[strum(message = "To UTF-7")]
     ToUtf7,
@@ -136,3 +214,409 @@ pub enum EscapeKind {
     #[strum(message = "From UTF-7")]
     FromUtf7,
 }
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum KdfKind {
+    #[default]
+    /// bcrypt (modular crypt format)
+    /// ex: '$2b$12$...'
+    #[strum(message = "bcrypt")]
+    Bcrypt,
+    /// scrypt (PHC string format)
+    /// ex: '$scrypt$ln=17,r=8,p=1$...'
+    #[strum(message = "scrypt")]
+    Scrypt,
+    /// Argon2id (PHC string format)
+    /// ex: '$argon2id$v=19$m=19456,t=2,p=1$...'
+    #[strum(message = "Argon2id")]
+    Argon2id,
+    /// PBKDF2-HMAC-SHA1 (PHC string format)
+    /// ex: '$pbkdf2$i=600000,l=20$...'
+    #[strum(message = "PBKDF2-SHA1")]
+    Pbkdf2Sha1,
+    /// PBKDF2-HMAC-SHA256 (PHC string format)
+    /// ex: '$pbkdf2-sha256$i=600000,l=32$...'
+    #[strum(message = "PBKDF2-SHA256")]
+    Pbkdf2Sha256,
+    /// PBKDF2-HMAC-SHA512 (PHC string format)
+    /// ex: '$pbkdf2-sha512$i=600000,l=64$...'
+    #[strum(message = "PBKDF2-SHA512")]
+    Pbkdf2Sha512,
+    /// MD5-crypt (crypt(3))
+    /// ex: '$1$saltsalt$...'
+    #[strum(message = "MD5-crypt")]
+    Md5Crypt,
+    /// Apache htpasswd MD5
+    /// ex: '$apr1$saltsalt$...'
+    #[strum(message = "APR1 (htpasswd)")]
+    Apr1,
+    /// SHA-256-crypt (crypt(3))
+    /// ex: '$5$rounds=5000$salt$...'
+    #[strum(message = "SHA-256-crypt")]
+    Sha256Crypt,
+    /// SHA-512-crypt (crypt(3), /etc/shadow)
+    /// ex: '$6$rounds=5000$salt$...'
+    #[strum(message = "SHA-512-crypt")]
+    Sha512Crypt,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum SymmetricCipher {
+    #[default]
+    /// AES with a 16 byte key
+    #[strum(message = "AES-128")]
+    Aes128,
+    /// AES with a 24 byte key
+    #[strum(message = "AES-192")]
+    Aes192,
+    /// AES with a 32 byte key
+    #[strum(message = "AES-256")]
+    Aes256,
+    /// ChaCha20-Poly1305 (rfc 8439), 32 byte key and 12 byte nonce
+    #[strum(message = "ChaCha20-Poly1305")]
+    ChaCha20Poly1305,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum BlockMode {
+    #[default]
+    /// cipher block chaining with PKCS#7 padding, 16 byte iv
+    #[strum(message = "CBC")]
+    Cbc,
+    /// electronic codebook with PKCS#7 padding, no iv
+    #[strum(message = "ECB")]
+    Ecb,
+    /// counter mode (128 bit big endian counter), 16 byte iv
+    #[strum(message = "CTR")]
+    Ctr,
+    /// galois/counter mode, 12 byte nonce, tag appended to the ciphertext
+    #[strum(message = "GCM")]
+    Gcm,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Direction {
+    #[default]
+    /// plain text in the editor, ciphertext out
+    #[strum(message = "Encrypt")]
+    Encrypt,
+    /// ciphertext in the editor, plain text out
+    #[strum(message = "Decrypt")]
+    Decrypt,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Encoding {
+    #[default]
+    /// hex bytes, whitespace and colons are ignored
+    /// ex: '00112233'
+    #[strum(message = "hex")]
+    Hex,
+    /// base64 (standard or url safe, padding optional)
+    /// ex: 'ABEiMw=='
+    #[strum(message = "b64")]
+    Base64,
+    /// utf-8 text
+    #[strum(message = "text")]
+    Text,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ClassicCipher {
+    #[default]
+    /// ROT13
+    /// ex: 'Hello' = 'Uryyb'
+    #[strum(message = "ROT13")]
+    Rot13,
+    /// ROT47 (printable ascii)
+    /// ex: 'Hello' = 'w6==@'
+    #[strum(message = "ROT47")]
+    Rot47,
+    /// Caesar encode with shift
+    /// ex: 'Hello' (shift 3) = 'Khoor'
+    #[strum(message = "Caesar Encode")]
+    CaesarEncode,
+    /// Caesar decode with shift
+    /// ex: 'Khoor' (shift 3) = 'Hello'
+    #[strum(message = "Caesar Decode")]
+    CaesarDecode,
+    /// list all 25 Caesar shifts
+    #[strum(message = "Caesar Brute Force")]
+    CaesarBruteForce,
+    /// Vigenère encode with key
+    /// ex: 'attackatdawn' (key 'lemon') = 'lxfopvefrnhr'
+    #[strum(message = "Vigenère Encode")]
+    VigenereEncode,
+    /// Vigenère decode with key
+    /// ex: 'lxfopvefrnhr' (key 'lemon') = 'attackatdawn'
+    #[strum(message = "Vigenère Decode")]
+    VigenereDecode,
+    /// Atbash (reversed alphabet)
+    /// ex: 'Hello' = 'Svool'
+    #[strum(message = "Atbash")]
+    Atbash,
+    /// affine encode, E(x) = (a x + b) mod 26
+    /// ex: 'affine' (a 5, b 8) = 'ihhwvc'
+    #[strum(message = "Affine Encode")]
+    AffineEncode,
+    /// affine decode, D(x) = a⁻¹ (x - b) mod 26
+    /// ex: 'ihhwvc' (a 5, b 8) = 'affine'
+    #[strum(message = "Affine Decode")]
+    AffineDecode,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum XorKind {
+    #[default]
+    /// xor the input bytes with a repeating key
+    /// ex: '48656c6c6f' (key '2a') = '624f464645'
+    #[strum(message = "XOR with key")]
+    WithKey,
+    /// try every single byte key, ranked by english-likeness
+    #[strum(message = "Single byte brute force")]
+    BruteForce,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Codec {
+    #[default]
+    /// gzip (rfc 1952)
+    #[strum(message = "gzip")]
+    Gzip,
+    /// zlib (rfc 1950)
+    #[strum(message = "zlib")]
+    Zlib,
+    /// raw deflate (rfc 1951)
+    #[strum(message = "deflate")]
+    Deflate,
+    /// zstandard (rfc 8878)
+    #[strum(message = "zstd")]
+    Zstd,
+    /// brotli (rfc 7932)
+    #[strum(message = "brotli")]
+    Brotli,
+    /// lz4 frame format
+    #[strum(message = "lz4")]
+    Lz4,
+    /// bzip2
+    #[strum(message = "bzip2")]
+    Bzip2,
+    /// xz
+    #[strum(message = "xz")]
+    Xz,
+    /// legacy lzma (lzma-alone, .lzma)
+    #[strum(message = "lzma")]
+    Lzma,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum CompressMode {
+    #[default]
+    /// compress the input bytes
+    #[strum(message = "Compress")]
+    Compress,
+    /// decompress the input bytes
+    #[strum(message = "Decompress")]
+    Decompress,
+    /// show the gzip header (filename, mtime, os)
+    #[strum(message = "Inspect header")]
+    Header,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum DataKind {
+    #[default]
+    /// JSON pretty print
+    /// ex: '{"a":[1,2]}' = '{\n  "a": [\n    1,\n    2\n  ]\n}'
+    #[strum(message = "JSON Format")]
+    JsonPretty,
+    /// JSON minify
+    /// ex: '{ "a": [1, 2] }' = '{"a":[1,2]}'
+    #[strum(message = "JSON Minify")]
+    JsonMinify,
+    /// JSON validation, errors are marked in the editor
+    #[strum(message = "JSON Validate")]
+    JsonValidate,
+    /// convert between JSON, YAML and TOML
+    #[strum(message = "Convert")]
+    Convert,
+    /// XML pretty print
+    #[strum(message = "XML Format")]
+    XmlPretty,
+    /// XML minify, comments and whitespace between elements are dropped
+    #[strum(message = "XML Minify")]
+    XmlMinify,
+    /// Exclusive XML Canonicalization without comments, the bytes XML signatures cover
+    #[strum(message = "XML Exclusive C14N")]
+    XmlC14n,
+    /// CSV / TSV to a JSON array
+    #[strum(message = "CSV to JSON")]
+    CsvToJson,
+    /// JSON array of objects or arrays to CSV / TSV
+    #[strum(message = "JSON to CSV")]
+    JsonToCsv,
+    /// MessagePack / CBOR / BSON bytes to JSON with type annotations
+    #[strum(message = "Binary to JSON")]
+    BinaryToJson,
+    /// annotated JSON to MessagePack / CBOR / BSON bytes
+    #[strum(message = "JSON to Binary")]
+    JsonToBinary,
+    /// protobuf wire format to a field tree, named after a .proto file when one is loaded
+    #[strum(message = "Protobuf decode")]
+    Protobuf,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Delimiter {
+    #[default]
+    /// comma separated values
+    #[strum(message = ",")]
+    Comma,
+    /// tab separated values
+    #[strum(message = "tab")]
+    Tab,
+    /// semicolon separated values
+    #[strum(message = ";")]
+    Semicolon,
+    /// pipe separated values
+    #[strum(message = "|")]
+    Pipe,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Quoting {
+    #[default]
+    /// quote fields only when needed
+    #[strum(message = "quote as needed")]
+    Necessary,
+    /// quote every field
+    #[strum(message = "quote all")]
+    Always,
+    /// quote fields that are not numbers
+    #[strum(message = "quote non-numeric")]
+    NonNumeric,
+    /// never quote fields
+    #[strum(message = "quote none")]
+    Never,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum DataFormat {
+    #[default]
+    /// JSON (rfc 8259)
+    #[strum(message = "JSON")]
+    Json,
+    /// YAML 1.2, single document
+    #[strum(message = "YAML")]
+    Yaml,
+    /// TOML 1.0
+    #[strum(message = "TOML")]
+    Toml,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum BinaryFormat {
+    #[default]
+    /// MessagePack
+    #[strum(message = "MessagePack")]
+    MessagePack,
+    /// CBOR (rfc 8949)
+    #[strum(message = "CBOR")]
+    Cbor,
+    /// BSON, as MongoDB canonical Extended JSON
+    #[strum(message = "BSON")]
+    Bson,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Asn1Kind {
+    #[default]
+    /// DER / BER tag-length-value tree from PEM, hex or base64 input
+    #[strum(message = "ASN.1 Dump")]
+    Dump,
+    /// X.509 certificate or PKCS#10 request: names, validity, key, extensions, fingerprints
+    #[strum(message = "Certificate / CSR")]
+    Certificate,
+    /// convert RSA, EC and Ed25519 keys between PEM, JWK and OpenSSH encodings
+    #[strum(message = "Key Convert")]
+    KeyConvert,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum KeyFormat {
+    #[default]
+    /// SubjectPublicKeyInfo, 'PUBLIC KEY'
+    #[strum(message = "SPKI")]
+    Spki,
+    /// PrivateKeyInfo, 'PRIVATE KEY'
+    #[strum(message = "PKCS#8")]
+    Pkcs8,
+    /// 'RSA PRIVATE KEY' / 'RSA PUBLIC KEY', or 'EC PRIVATE KEY' for EC keys
+    #[strum(message = "PKCS#1 / SEC1")]
+    Pkcs1,
+    /// JSON Web Key (rfc 7517)
+    #[strum(message = "JWK")]
+    Jwk,
+    /// OpenSSH public key line
+    #[strum(message = "OpenSSH")]
+    OpenSsh,
+    /// SHA-256 JWK thumbprint (rfc 7638)
+    #[strum(message = "JWK Thumbprint")]
+    Thumbprint,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum NumberKind {
+    #[default]
+    /// arbitrary precision integer between bases 2 to 36, with two's complement views
+    /// ex: '-1' = 'ff' (8 bit)
+    #[strum(message = "Base Convert")]
+    Radix,
+    /// IEEE 754 fields of a decimal number or a 0x bit pattern
+    /// ex: '0.1' = '0x3DCCCCCD' (f32)
+    #[strum(message = "IEEE 754 Float")]
+    Float,
+    /// hex or 0x byte string read as the fields of a python struct format
+    /// ex: '<HI' '3412 78563412' = '4660', '305419896'
+    #[strum(message = "Struct Unpack")]
+    Unpack,
+    /// values written as the fields of a python struct format
+    /// ex: '>HI' '1, 2' = '000100000002'
+    #[strum(message = "Struct Pack")]
+    Pack,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq, VariantArray, EnumMessage)]
+#[strum(serialize_all = "kebab-case")]
+pub enum FloatFormat {
+    /// IEEE 754 half precision
+    #[strum(message = "f16")]
+    F16,
+    /// bfloat16, f32 with a truncated mantissa
+    #[strum(message = "bf16")]
+    Bf16,
+    #[default]
+    /// IEEE 754 single precision
+    #[strum(message = "f32")]
+    F32,
+    /// IEEE 754 double precision
+    #[strum(message = "f64")]
+    F64,
+}
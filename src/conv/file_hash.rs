@@ -0,0 +1,121 @@
+use crate::conv::enum_variants::Digest;
+use crate::conv::hasher::StreamHasher;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+
+const CHUNK_SIZE: usize = 1 << 20;
+
+pub type Digests = Vec<(Digest, Box<[u8]>)>;
+
+/// hashes a file on a background thread, chunk by chunk
+pub struct FileHash {
+    pub path: PathBuf,
+    pub digests: Vec<Digest>,
+    pub result: Option<Result<Digests, String>>,
+    total: Arc<AtomicU64>,
+    read: Arc<AtomicU64>,
+    cancel: Arc<AtomicBool>,
+    rx: Receiver<io::Result<Digests>>,
+}
+
+impl FileHash {
+    pub fn spawn(path: PathBuf, digests: Vec<Digest>) -> Self {
+        let total = Arc::new(AtomicU64::new(0));
+        let read = Arc::new(AtomicU64::new(0));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (tx, rx) = channel();
+
+        {
+            let (path, digests) = (path.clone(), digests.clone());
+            let (total, read, cancel) = (total.clone(), read.clone(), cancel.clone());
+            thread::spawn(move || {
+                let _ = tx.send(hash_file(path, digests, &total, &read, &cancel));
+            });
+        }
+
+        Self {
+            path,
+            digests,
+            result: None,
+            total,
+            read,
+            cancel,
+            rx,
+        }
+    }
+
+    /// returns true while the worker is still running
+    pub fn poll(&mut self) -> bool {
+        if self.result.is_some() {
+            return false;
+        }
+        match self.rx.try_recv() {
+            Ok(res) => {
+                self.result = Some(res.map_err(|e| e.to_string()));
+                false
+            },
+            Err(TryRecvError::Empty) => true,
+            Err(TryRecvError::Disconnected) => {
+                self.result = Some(Err("hash worker terminated".to_owned()));
+                false
+            },
+        }
+    }
+
+    pub fn cancel(&self) { self.cancel.store(true, Ordering::Relaxed); }
+
+    pub fn read(&self) -> u64 { self.read.load(Ordering::Relaxed) }
+
+    pub fn total(&self) -> u64 { self.total.load(Ordering::Relaxed) }
+
+    pub fn progress(&self) -> f32 {
+        match self.total() {
+            0 => 0.0,
+            total => self.read() as f32 / total as f32,
+        }
+    }
+}
+
+impl Drop for FileHash {
+    fn drop(&mut self) { self.cancel(); }
+}
+
+fn hash_file(
+    path: PathBuf,
+    digests: Vec<Digest>,
+    total: &AtomicU64,
+    read: &AtomicU64,
+    cancel: &AtomicBool,
+) -> io::Result<Digests> {
+    let mut file = File::open(path)?;
+    total.store(file.metadata()?.len(), Ordering::Relaxed);
+
+    let mut hashers = digests
+        .iter()
+        .map(|d| StreamHasher::new(*d))
+        .collect::<Vec<_>>();
+    let mut buf = vec![0; CHUNK_SIZE];
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "cancelled"));
+        }
+        let n = match file.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        hashers.iter_mut().for_each(|h| h.update(&buf[..n]));
+        read.fetch_add(n as u64, Ordering::Relaxed);
+    }
+
+    Ok(digests
+        .into_iter()
+        .zip(hashers.into_iter().map(StreamHasher::finalize))
+        .collect())
+}
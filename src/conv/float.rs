@@ -0,0 +1,231 @@
+use crate::conv::encoding;
+use crate::conv::enum_variants::{Encoding, FloatFormat};
+use half::{bf16, f16};
+use num_bigint::BigUint;
+use strum::EnumMessage;
+
+/// (exponent, mantissa) field widths
+fn layout(format: FloatFormat) -> (u32, u32) {
+    match format {
+        FloatFormat::F16 => (5, 10),
+        FloatFormat::Bf16 => (8, 7),
+        FloatFormat::F32 => (8, 23),
+        FloatFormat::F64 => (11, 52),
+    }
+}
+
+/// a decimal number, or a `0x` bit pattern, split into sign, exponent and mantissa
+pub fn inspect(text: &str, format: FloatFormat) -> Result<String, String> {
+    let (exp_bits, man_bits) = layout(format);
+    let width = 1 + exp_bits + man_bits;
+    let text = text.trim();
+    let bits = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => {
+            let bytes = encoding::decode(hex, Encoding::Hex)?;
+            if bytes.len() * 8 != width as usize {
+                return Err(format!(
+                    "{} needs {} hex digits, not {}",
+                    format.get_message().unwrap_or_default(),
+                    width / 4,
+                    bytes.len() * 2
+                ));
+            }
+            bytes.iter().fold(0u64, |acc, &b| acc << 8 | b as u64)
+        },
+        None => encode(text, format).map_err(|e| format!("{:?}: {}", text, e))?,
+    };
+
+    let sign = bits >> (width - 1);
+    let exp = (bits >> man_bits) & ((1 << exp_bits) - 1);
+    let man = bits & ((1 << man_bits) - 1);
+    let bias = (1i64 << (exp_bits - 1)) - 1;
+    let max_exp = (1 << exp_bits) - 1;
+    let class = match (exp, man) {
+        (0, 0) => "zero",
+        (0, _) => "subnormal",
+        (e, 0) if e == max_exp => "infinity",
+        (e, m) if e == max_exp && m >> (man_bits - 1) == 1 => "quiet NaN",
+        (e, _) if e == max_exp => "signalling NaN",
+        _ => "normal",
+    };
+    let unbiased = match exp {
+        0 => 1 - bias,
+        e => e as i64 - bias,
+    };
+
+    let mut lines = vec![
+        format!("hex       0x{:0>digits$X}", bits, digits = width as usize / 4),
+        format!(
+            "binary    {} {:0>e$b} {:0>m$b}",
+            sign,
+            exp,
+            man,
+            e = exp_bits as usize,
+            m = man_bits as usize
+        ),
+        format!("sign      {} ({})", sign, if sign == 1 { "-" } else { "+" }),
+        format!("exponent  {} (biased {}, bias {})", unbiased, exp, bias),
+        format!("mantissa  0x{:X}", man),
+        format!("class     {}", class),
+        format!("value     {}", value(bits, format)),
+    ];
+    if exp != max_exp {
+        lines.push(format!("exact     {}", exact(sign == 1, exp, man, bias, man_bits)));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// the bit pattern nearest to a decimal number
+pub fn encode(text: &str, format: FloatFormat) -> Result<u64, String> {
+    let value = text.parse::<f64>().map_err(|e| e.to_string())?;
+    Ok(match format {
+        // no parser of their own, going through f64 would round twice
+        FloatFormat::F16 | FloatFormat::Bf16 if value.is_finite() && value != 0.0 => {
+            let (exp_bits, man_bits) = layout(format);
+            round_decimal(text, exp_bits, man_bits)?
+        },
+        FloatFormat::F16 => f16::from_f64(value).to_bits() as u64,
+        FloatFormat::Bf16 => bf16::from_f64(value).to_bits() as u64,
+        // parsed at their own precision to avoid double rounding
+        FloatFormat::F32 => text.parse::<f32>().map_err(|e| e.to_string())?.to_bits() as u64,
+        FloatFormat::F64 => value.to_bits(),
+    })
+}
+
+/// sign, digits and power of ten of a decimal number, ex: '-1.5e3' = (true, 15, 2)
+fn decimal(text: &str) -> Option<(bool, BigUint, i64)> {
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(t) => (true, t),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (mantissa, exp) = match text.split_once(['e', 'E']) {
+        Some((m, e)) => (m, e.parse::<i64>().ok()?),
+        None => (text, 0),
+    };
+    let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = BigUint::parse_bytes(format!("{}{}", int, frac).as_bytes(), 10)?;
+    Some((negative, digits, exp.checked_sub(frac.len() as i64)?))
+}
+
+/// rounds the exact value of a finite, non-zero decimal to nearest, ties to even
+fn round_decimal(text: &str, exp_bits: u32, man_bits: u32) -> Result<u64, String> {
+    let (negative, digits, e10) = decimal(text).ok_or("not a decimal number")?;
+    let e10_abs = u32::try_from(e10.unsigned_abs()).map_err(|_| "exponent out of range")?;
+    let (num, den) = match e10 >= 0 {
+        true => (digits * BigUint::from(10u8).pow(e10_abs), BigUint::from(1u8)),
+        false => (digits, BigUint::from(10u8).pow(e10_abs)),
+    };
+
+    // floor(log2(num / den)), clamped to the subnormal exponent
+    let mut e = num.bits() as i64 - den.bits() as i64;
+    let below = match e >= 0 {
+        true => num < &den << e as usize,
+        false => (&num << (-e) as usize) < den,
+    };
+    if below {
+        e -= 1;
+    }
+    let bias = (1i64 << (exp_bits - 1)) - 1;
+    let e = e.max(1 - bias);
+
+    // num / den * 2^(man_bits - e), an integer of at most man_bits + 1 bits once rounded
+    let shift = man_bits as i64 - e;
+    let (num, den) = match shift >= 0 {
+        true => (num << shift as usize, den),
+        false => (num, den << (-shift) as usize),
+    };
+    let mut q = &num / &den;
+    let twice = (num % &den) << 1;
+    if twice > den || (twice == den && q.bit(0)) {
+        q += 1u8;
+    }
+
+    let hidden = BigUint::from(1u8) << man_bits as usize;
+    let (mut biased, mut man) = match q >= hidden {
+        true => ((e + bias) as u64, q - &hidden),
+        false => (0, q),
+    };
+    if man == hidden {
+        // rounded up into the next binade
+        biased += 1;
+        man = BigUint::from(0u8);
+    }
+    let max_exp = (1u64 << exp_bits) - 1;
+    if biased >= max_exp {
+        (biased, man) = (max_exp, BigUint::from(0u8));
+    }
+    let man = man.to_u64_digits().first().copied().unwrap_or(0);
+    Ok((negative as u64) << (exp_bits + man_bits) | biased << man_bits | man)
+}
+
+/// shortest decimal that round-trips
+fn value(bits: u64, format: FloatFormat) -> String {
+    match format {
+        FloatFormat::F16 => format!("{:?}", f16::from_bits(bits as u16)),
+        FloatFormat::Bf16 => format!("{:?}", bf16::from_bits(bits as u16)),
+        FloatFormat::F32 => format!("{:?}", f32::from_bits(bits as u32)),
+        FloatFormat::F64 => format!("{:?}", f64::from_bits(bits)),
+    }
+}
+
+/// every digit of mantissa * 2^exponent, binary fractions always terminate in decimal
+fn exact(negative: bool, exp: u64, man: u64, bias: i64, man_bits: u32) -> String {
+    let (m, e) = match exp {
+        0 => (man, 1 - bias - man_bits as i64),
+        exp => (man | 1 << man_bits, exp as i64 - bias - man_bits as i64),
+    };
+    let sign = if negative { "-" } else { "" };
+    if e >= 0 {
+        return format!("{}{}", sign, BigUint::from(m) << e as usize);
+    }
+    // m / 2^k = m * 5^k / 10^k
+    let k = (-e) as usize;
+    let digits = (BigUint::from(m) * BigUint::from(5u8).pow(k as u32)).to_string();
+    let digits = format!("{:0>width$}", digits, width = k + 1);
+    let (int, frac) = digits.split_at(digits.len() - k);
+    let frac = frac.trim_end_matches('0');
+    match frac {
+        "" => format!("{}{}", sign, int),
+        frac => format!("{}{}.{}", sign, int, frac),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f16(text: &str) -> u64 { encode(text, FloatFormat::F16).unwrap() }
+
+    fn bf16(text: &str) -> u64 { encode(text, FloatFormat::Bf16).unwrap() }
+
+    #[test]
+    fn ties_to_even() {
+        assert_eq!(f16("1"), 0x3c00);
+        assert_eq!(f16("2049"), 0x6800);
+        assert_eq!(f16("2051"), 0x6802);
+        // the f64 nearest to this is the tie 2049, rounding it again would give 0x6800
+        assert_eq!(f16("2049.00000000000000001"), 0x6801);
+        assert_eq!(bf16("1.00390625"), 0x3f80);
+        assert_eq!(bf16("1.01171875"), 0x3f82);
+        assert_eq!(bf16("-2.5"), 0xc020);
+    }
+
+    #[test]
+    fn subnormals() {
+        assert_eq!(f16("5.9604644775390625e-8"), 0x0001);
+        assert_eq!(f16("2.98023223876953125e-8"), 0x0000);
+        assert_eq!(f16("2.9802322387695313e-8"), 0x0001);
+        assert_eq!(f16("6.097555160522461e-5"), 0x03ff);
+        assert_eq!(bf16("9.183549615799121e-41"), 0x0001);
+    }
+
+    #[test]
+    fn overflow() {
+        assert_eq!(f16("65519.99"), 0x7bff);
+        assert_eq!(f16("65520"), 0x7c00);
+        assert_eq!(f16("-1e10"), 0xfc00);
+        assert_eq!(bf16("3.39e38"), 0x7f7f);
+        assert_eq!(bf16("3.4e38"), 0x7f80);
+        assert_eq!(round_decimal("1e400", 5, 10).unwrap(), 0x7c00);
+    }
+}
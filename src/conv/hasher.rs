@@ -1,21 +1,78 @@
+use crate::conv::enum_variants::{Digest, DigestFormat};
+use base64::engine::general_purpose;
+use base64::Engine as _;
 use digest::DynDigest;
+use itertools::Itertools;
 use rustc_serialize::hex::ToHex;
+use sha1::{Digest as _, Sha1};
+use strum::VariantArray;
 
-fn use_hasher(mut hasher: Box<dyn DynDigest>, data: &[u8]) -> Box<[u8]> {
-    hasher.update(data);
-    hasher.finalize_reset()
+fn select_hasher(digest: Digest) -> StreamHasher {
+    match digest {
+        Digest::Md5 => StreamHasher::Md5(md5::Context::new()),
+        Digest::Sha1 => StreamHasher::Sha1(Sha1::new()),
+        Digest::Sha224 => StreamHasher::Dyn(Box::new(sha2::Sha224::default())),
+        Digest::Sha256 => StreamHasher::Dyn(Box::new(sha2::Sha256::default())),
+        Digest::Sha384 => StreamHasher::Dyn(Box::new(sha2::Sha384::default())),
+        Digest::Sha512 => StreamHasher::Dyn(Box::new(sha2::Sha512::default())),
+    }
+}
+
+pub fn digest_bytes(digest: Digest, data: &[u8]) -> Box<[u8]> {
+    let mut h = StreamHasher::new(digest);
+    h.update(data);
+    h.finalize()
+}
+
+/// incremental hasher over every supported digest
+pub enum StreamHasher {
+    Md5(md5::Context),
+    Sha1(Sha1),
+    Dyn(Box<dyn DynDigest + Send>),
+}
+
+impl StreamHasher {
+    pub fn new(digest: Digest) -> Self { select_hasher(digest) }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            StreamHasher::Md5(h) => h.consume(data),
+            StreamHasher::Sha1(h) => sha1::Digest::update(h, data),
+            StreamHasher::Dyn(h) => h.update(data),
+        }
+    }
+
+    pub fn finalize(self) -> Box<[u8]> {
+        match self {
+            StreamHasher::Md5(h) => Box::new(h.compute().0),
+            StreamHasher::Sha1(h) => h.finalize().to_vec().into_boxed_slice(),
+            StreamHasher::Dyn(mut h) => h.finalize_reset(),
+        }
+    }
 }
 
-fn select_hasher(s: &str) -> Box<dyn DynDigest> {
-    match s {
-        "sha224" => Box::new(sha2::Sha224::default()),
-        "sha256" => Box::new(sha2::Sha256::default()),
-        "sha384" => Box::new(sha2::Sha384::default()),
-        "sha512" => Box::new(sha2::Sha512::default()),
-        _ => unimplemented!("unsupported digest: {}", s),
+pub fn format_digest(digest: &[u8], format: DigestFormat) -> String {
+    match format {
+        DigestFormat::LowerHex => digest.to_hex(),
+        DigestFormat::UpperHex => digest.to_hex().to_uppercase(),
+        DigestFormat::Fingerprint => digest.iter().map(|x| format!("{:02X}", x)).join(":"),
+        DigestFormat::Base64 => general_purpose::STANDARD.encode(digest),
+        DigestFormat::Base64Url => general_purpose::URL_SAFE_NO_PAD.encode(digest),
+        DigestFormat::ByteString => {
+            format!(r"0x{}", digest.iter().map(|x| format!("{:02x}", x)).join(r", 0x"))
+        },
     }
 }
 
-pub fn hasher<'a>(s: &'a str, text: &'a str) -> String {
-    use_hasher(select_hasher(s), text.as_ref()).to_hex()
+pub fn match_digest(digest: &[u8], expected: &str) -> Option<DigestFormat> {
+    if expected.is_empty() {
+        return None;
+    }
+    DigestFormat::VARIANTS.iter().copied().find(|f| {
+        let text = format_digest(digest, *f);
+        match f {
+            DigestFormat::Base64 | DigestFormat::Base64Url => text == expected,
+            _ => text.eq_ignore_ascii_case(expected),
+        }
+    })
 }
@@ -0,0 +1,152 @@
+use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
+use serde_json::{Deserializer, Error, Serializer, Value};
+
+#[derive(Clone, PartialEq)]
+pub struct JsonParams {
+    pub indent: usize,
+    pub sort_keys: bool,
+}
+
+impl Default for JsonParams {
+    fn default() -> Self {
+        Self {
+            indent: 2,
+            sort_keys: false,
+        }
+    }
+}
+
+pub fn pretty(text: &str, params: &JsonParams) -> Result<String, Error> {
+    let value = parse(text, params)?;
+    let indent = " ".repeat(params.indent);
+    let mut buf = Vec::new();
+    let formatter = PrettyFormatter::with_indent(indent.as_bytes());
+    let mut ser = Serializer::with_formatter(&mut buf, formatter);
+    value.serialize(&mut ser)?;
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+pub fn minify(text: &str, params: &JsonParams) -> Result<String, Error> {
+    serde_json::to_string(&parse(text, params)?)
+}
+
+pub fn validate(text: &str) -> Result<String, Error> {
+    let value = serde_json::from_str::<Value>(text)?;
+    let kind = match &value {
+        Value::Null => "null".to_owned(),
+        Value::Bool(_) => "boolean".to_owned(),
+        Value::Number(_) => "number".to_owned(),
+        Value::String(_) => "string".to_owned(),
+        Value::Array(a) => format!("array, {} items", a.len()),
+        Value::Object(o) => format!("object, {} keys", o.len()),
+    };
+    Ok(format!("✔ valid JSON ({})", kind))
+}
+
+/// json string escape, non ascii as \uXXXX with surrogate pairs
+pub fn escape(text: &str) -> String {
+    let mut buf = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => buf.push_str(r#"\""#),
+            '\\' => buf.push_str(r"\\"),
+            '\n' => buf.push_str(r"\n"),
+            '\r' => buf.push_str(r"\r"),
+            '\t' => buf.push_str(r"\t"),
+            '\u{8}' => buf.push_str(r"\b"),
+            '\u{c}' => buf.push_str(r"\f"),
+            c if c.is_ascii() && !c.is_ascii_control() => buf.push(c),
+            c => {
+                let mut b = [0; 2];
+                for u in c.encode_utf16(&mut b) {
+                    buf.push_str(&format!(r"\u{:04x}", u));
+                }
+            },
+        }
+    }
+    buf
+}
+
+/// accepts the string with or without the surrounding quotes
+pub fn unescape(text: &str) -> Result<String, Error> {
+    let text = text.trim();
+    match text.len() > 1 && text.starts_with('"') && text.ends_with('"') {
+        true => serde_json::from_str::<String>(text),
+        false => serde_json::from_str::<String>(&format!("\"{}\"", text)),
+    }
+}
+
+/// decodes json that was encoded into json strings, possibly several times,
+/// whether it is the whole input or embedded in log lines
+pub fn unwrap(text: &str) -> String {
+    if let Ok(value) = serde_json::from_str::<Value>(text) {
+        return to_pretty(unwrap_value(value));
+    }
+    text.lines().map(unwrap_line).collect::<Vec<_>>().join("\n")
+}
+
+fn unwrap_line(line: &str) -> String {
+    let mut buf = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(i) = rest.find(['{', '[', '"']) {
+        let mut stream = Deserializer::from_str(&rest[i..]).into_iter::<Value>();
+        match stream.next() {
+            Some(Ok(value)) => {
+                let end = i + stream.byte_offset();
+                buf.push_str(&rest[..i]);
+                match unwrap_value(value) {
+                    value @ (Value::Object(_) | Value::Array(_)) => buf.push_str(&to_pretty(value)),
+                    _ => buf.push_str(&rest[i..end]),
+                }
+                rest = &rest[end..];
+            },
+            _ => {
+                buf.push_str(&rest[..=i]);
+                rest = &rest[i + 1..];
+            },
+        }
+    }
+    buf.push_str(rest);
+    buf
+}
+
+fn unwrap_value(value: Value) -> Value {
+    match value {
+        Value::String(s) => match serde_json::from_str::<Value>(&s) {
+            Ok(v @ (Value::String(_) | Value::Object(_) | Value::Array(_))) => unwrap_value(v),
+            _ => Value::String(s),
+        },
+        Value::Array(a) => Value::Array(a.into_iter().map(unwrap_value).collect()),
+        Value::Object(o) => {
+            Value::Object(o.into_iter().map(|(k, v)| (k, unwrap_value(v))).collect())
+        },
+        v => v,
+    }
+}
+
+fn to_pretty(value: Value) -> String {
+    match value {
+        Value::String(s) => s,
+        v => serde_json::to_string_pretty(&v).unwrap_or_default(),
+    }
+}
+
+fn parse(text: &str, params: &JsonParams) -> Result<Value, Error> {
+    let mut value = serde_json::from_str::<Value>(text)?;
+    if params.sort_keys {
+        sort_keys(&mut value);
+    }
+    Ok(value)
+}
+
+fn sort_keys(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.sort_keys();
+            map.values_mut().for_each(sort_keys);
+        },
+        Value::Array(a) => a.iter_mut().for_each(sort_keys),
+        _ => {},
+    }
+}
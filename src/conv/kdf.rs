@@ -0,0 +1,255 @@
+use crate::conv::enum_variants::KdfKind;
+use crate::conv::unix_crypt::{self, ShaCrypt};
+use argon2::Argon2;
+use password_hash::rand_core::OsRng;
+use password_hash::{PasswordHash, PasswordHasher, SaltString};
+use pbkdf2::Pbkdf2;
+use scrypt::Scrypt;
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::thread;
+
+/// memory a single hash may take, scrypt and argon2 allocate all of it up front
+const MAX_MEMORY: u64 = 1 << 30;
+
+#[derive(Clone, PartialEq)]
+pub struct KdfParams {
+    pub bcrypt_cost: u32,
+    pub scrypt_log_n: u8,
+    pub scrypt_r: u32,
+    pub scrypt_p: u32,
+    pub argon2_m_cost: u32,
+    pub argon2_t_cost: u32,
+    pub argon2_p_cost: u32,
+    pub pbkdf2_rounds: u32,
+    pub sha_crypt_rounds: u32,
+    /// htpasswd / shadow user name, prefixed to generated crypt(3) hashes
+    pub user: String,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        // owasp password storage cheat sheet
+        Self {
+            bcrypt_cost: bcrypt::DEFAULT_COST,
+            scrypt_log_n: 17,
+            scrypt_r: 8,
+            scrypt_p: 1,
+            argon2_m_cost: argon2::Params::DEFAULT_M_COST,
+            argon2_t_cost: argon2::Params::DEFAULT_T_COST,
+            argon2_p_cost: argon2::Params::DEFAULT_P_COST,
+            pbkdf2_rounds: 600_000,
+            sha_crypt_rounds: 5000,
+            user: String::new(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct KdfInput {
+    kind: KdfKind,
+    params: KdfParams,
+    password: String,
+    hash: String,
+}
+
+/// slow hashes run on a worker thread and are only recomputed when an input changes,
+/// this also keeps the random salt stable between frames
+#[derive(Default)]
+pub struct KdfCache {
+    input: Option<KdfInput>,
+    output: Option<String>,
+    rx: Option<Receiver<String>>,
+    /// the input changed since the running worker was started
+    stale: bool,
+}
+
+impl KdfCache {
+    /// the output for these inputs, None while the worker is busy
+    pub fn memorise(
+        &mut self,
+        kind: KdfKind,
+        params: &KdfParams,
+        password: &str,
+        hash: &str,
+    ) -> Option<String> {
+        let input = KdfInput {
+            kind,
+            params: params.clone(),
+            password: password.to_owned(),
+            hash: hash.to_owned(),
+        };
+        if self.input.as_ref() != Some(&input) {
+            self.input = Some(input);
+            self.output = None;
+            self.stale = true;
+        }
+        self.poll();
+        // at most one worker, edits made while it runs are picked up once it is done
+        if self.stale && self.rx.is_none() {
+            self.spawn();
+        }
+        self.output.clone()
+    }
+
+    /// stops waiting for the worker, which can not be interrupted, so no new one is spawned
+    /// until it is done and its result replaces "cancelled"
+    pub fn cancel(&mut self) {
+        self.stale = false;
+        self.output = Some("cancelled".to_owned());
+    }
+
+    /// a worker is running, its result is picked up by the next `memorise`
+    pub fn busy(&self) -> bool { self.rx.is_some() }
+
+    fn spawn(&mut self) {
+        let Some(input) = self.input.clone() else {
+            return;
+        };
+        let (tx, rx) = channel();
+        thread::spawn(move || {
+            let output = match input.hash.trim() {
+                "" => generate(input.kind, &input.params, &input.password),
+                hash => verify(&input.password, hash),
+            };
+            let _ = tx.send(output.unwrap_or_else(|e| e));
+        });
+        self.rx = Some(rx);
+        self.stale = false;
+    }
+
+    fn poll(&mut self) {
+        let Some(rx) = &self.rx else {
+            return;
+        };
+        let output = match rx.try_recv() {
+            Ok(output) => output,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => "kdf worker terminated".to_owned(),
+        };
+        self.rx = None;
+        if !self.stale {
+            self.output = Some(output);
+        }
+    }
+}
+
+pub fn generate(kind: KdfKind, params: &KdfParams, password: &str) -> Result<String, String> {
+    let salt = SaltString::generate(&mut OsRng);
+    let password = password.as_bytes();
+    let hash = match kind {
+        KdfKind::Md5Crypt | KdfKind::Apr1 | KdfKind::Sha256Crypt | KdfKind::Sha512Crypt => {
+            let hash = match kind {
+                KdfKind::Md5Crypt => unix_crypt::md5_crypt(password, &unix_crypt::gen_salt(8), "1"),
+                KdfKind::Apr1 => unix_crypt::md5_crypt(password, &unix_crypt::gen_salt(8), "apr1"),
+                _ => {
+                    let alg = match kind {
+                        KdfKind::Sha256Crypt => ShaCrypt::Sha256,
+                        _ => ShaCrypt::Sha512,
+                    };
+                    let rounds = Some(params.sha_crypt_rounds).filter(|r| *r != 5000);
+                    unix_crypt::sha_crypt(alg, password, &unix_crypt::gen_salt(16), rounds)
+                },
+            };
+            return Ok(match params.user.as_str() {
+                "" => hash,
+                user => format!("{}:{}", user, hash),
+            });
+        },
+        KdfKind::Bcrypt => {
+            return bcrypt::hash(password, params.bcrypt_cost).map_err(|e| e.to_string())
+        },
+        KdfKind::Scrypt => {
+            check_memory(scrypt_memory(
+                params.scrypt_log_n.into(),
+                params.scrypt_r,
+                params.scrypt_p,
+            ))?;
+            let p = scrypt::Params::new(
+                params.scrypt_log_n,
+                params.scrypt_r,
+                params.scrypt_p,
+                scrypt::Params::RECOMMENDED_LEN,
+            )
+            .map_err(|e| e.to_string())?;
+            Scrypt.hash_password_customized(password, None, None, p, &salt)
+        },
+        KdfKind::Argon2id => {
+            check_memory(u64::from(params.argon2_m_cost) << 10)?;
+            let p = argon2::Params::new(
+                params.argon2_m_cost,
+                params.argon2_t_cost,
+                params.argon2_p_cost,
+                None,
+            )
+            .map_err(|e| e.to_string())?;
+            Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, p)
+                .hash_password(password, &salt)
+        },
+        KdfKind::Pbkdf2Sha1 | KdfKind::Pbkdf2Sha256 | KdfKind::Pbkdf2Sha512 => {
+            let (alg, len) = match kind {
+                KdfKind::Pbkdf2Sha1 => (pbkdf2::Algorithm::Pbkdf2Sha1, 20),
+                KdfKind::Pbkdf2Sha256 => (pbkdf2::Algorithm::Pbkdf2Sha256, 32),
+                _ => (pbkdf2::Algorithm::Pbkdf2Sha512, 64),
+            };
+            let p = pbkdf2::Params {
+                rounds: params.pbkdf2_rounds,
+                output_length: len,
+            };
+            Pbkdf2.hash_password_customized(password, Some(alg.ident()), None, p, &salt)
+        },
+    };
+    hash.map(|h| h.to_string()).map_err(|e| e.to_string())
+}
+
+pub fn verify(password: &str, hash: &str) -> Result<String, String> {
+    // htpasswd 'user:hash' and shadow 'user:hash:...' entries
+    let hash = hash.split(':').find(|f| f.starts_with('$')).unwrap_or(hash);
+    let matched = if let Some(m) = unix_crypt::verify(password.as_bytes(), hash) {
+        m
+    } else if hash.starts_with("$2") {
+        bcrypt::verify(password, hash).map_err(|e| e.to_string())?
+    } else {
+        let parsed = PasswordHash::new(hash).map_err(|e| e.to_string())?;
+        // the parameters come from the pasted hash, check them like generated ones
+        let param = |name: &str, default: u32| parsed.params.get_decimal(name).unwrap_or(default);
+        match parsed.algorithm.as_str() {
+            "scrypt" => check_memory(scrypt_memory(
+                param("ln", scrypt::Params::RECOMMENDED_LOG_N.into()),
+                param("r", scrypt::Params::RECOMMENDED_R),
+                param("p", scrypt::Params::RECOMMENDED_P),
+            ))?,
+            "argon2id" | "argon2i" | "argon2d" => {
+                check_memory(u64::from(param("m", argon2::Params::DEFAULT_M_COST)) << 10)?
+            },
+            _ => {},
+        }
+        match parsed.verify_password(&[&Argon2::default(), &Scrypt, &Pbkdf2], password) {
+            Ok(()) => true,
+            Err(password_hash::Error::Password) => false,
+            Err(e) => return Err(e.to_string()),
+        }
+    };
+    Ok(match matched {
+        true => "✔ match".to_owned(),
+        false => "✖ mismatch".to_owned(),
+    })
+}
+
+/// scrypt's V array plus the p blocks of B, 128 * r bytes each
+fn scrypt_memory(log_n: u32, r: u32, p: u32) -> u64 {
+    1u64.checked_shl(log_n)
+        .unwrap_or(u64::MAX)
+        .saturating_add(p.into())
+        .saturating_mul(128 * u64::from(r))
+}
+
+fn check_memory(bytes: u64) -> Result<(), String> {
+    match bytes > MAX_MEMORY {
+        true => Err(format!(
+            "needs {} MiB of memory, the limit is {} MiB",
+            bytes >> 20,
+            MAX_MEMORY >> 20
+        )),
+        false => Ok(()),
+    }
+}
@@ -0,0 +1,613 @@
+use crate::conv::asn1::{self, Node, BIT_STRING, INTEGER, OCTET_STRING, OID, SEQUENCE};
+use crate::conv::enum_variants::{Digest, DigestFormat, KeyFormat};
+use crate::conv::hasher::{digest_bytes, format_digest};
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine as _;
+use p256::elliptic_curve::sec1::{FromEncodedPoint, ModulusSize, ToEncodedPoint};
+use p256::elliptic_curve::{AffinePoint, CurveArithmetic, FieldBytesSize, SecretKey};
+use serde_json::{Map, Value};
+
+const RSA: &str = "1.2.840.113549.1.1.1";
+const EC: &str = "1.2.840.10045.2.1";
+const ED25519: &str = "1.3.101.112";
+
+struct Curve {
+    oid: &'static str,
+    jwk: &'static str,
+    ssh: Option<&'static str>,
+    /// bytes per coordinate
+    size: usize,
+    /// the uncompressed public point of a private scalar
+    public: fn(&[u8]) -> Result<Vec<u8>, String>,
+}
+
+static CURVES: [Curve; 4] = [
+    Curve {
+        oid: "1.2.840.10045.3.1.7",
+        jwk: "P-256",
+        ssh: Some("nistp256"),
+        size: 32,
+        public: public_point::<p256::NistP256>,
+    },
+    Curve {
+        oid: "1.3.132.0.34",
+        jwk: "P-384",
+        ssh: Some("nistp384"),
+        size: 48,
+        public: public_point::<p384::NistP384>,
+    },
+    Curve {
+        oid: "1.3.132.0.35",
+        jwk: "P-521",
+        ssh: Some("nistp521"),
+        size: 66,
+        public: public_point::<p521::NistP521>,
+    },
+    Curve {
+        oid: "1.3.132.0.10",
+        jwk: "secp256k1",
+        ssh: None,
+        size: 32,
+        public: public_point::<k256::Secp256k1>,
+    },
+];
+
+fn public_point<C>(d: &[u8]) -> Result<Vec<u8>, String>
+where
+    C: CurveArithmetic,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    let key = SecretKey::<C>::from_slice(d).map_err(|_| "invalid EC private key".to_owned())?;
+    Ok(key.public_key().to_encoded_point(false).as_bytes().to_vec())
+}
+
+struct RsaPrivate {
+    d: Vec<u8>,
+    p: Vec<u8>,
+    q: Vec<u8>,
+    dp: Vec<u8>,
+    dq: Vec<u8>,
+    qi: Vec<u8>,
+}
+
+enum Key {
+    Rsa {
+        n: Vec<u8>,
+        e: Vec<u8>,
+        private: Option<RsaPrivate>,
+    },
+    Ec {
+        curve: &'static Curve,
+        /// uncompressed point, 04 || x || y
+        point: Vec<u8>,
+        d: Option<Vec<u8>>,
+    },
+    Ed25519 {
+        public: [u8; 32],
+        seed: Option<[u8; 32]>,
+    },
+}
+
+/// reads a PEM / DER key, a JWK (or the first key of a JWKS) or an OpenSSH public key
+pub fn convert(text: &str, format: KeyFormat) -> Result<String, String> {
+    let key = read(text.trim())?;
+    match format {
+        KeyFormat::Pkcs1 => key.pkcs1().map(|(label, der)| pem(label, &der)),
+        KeyFormat::Pkcs8 => key.pkcs8().map(|der| pem("PRIVATE KEY", &der)),
+        KeyFormat::Spki => key.spki().map(|der| pem("PUBLIC KEY", &der)),
+        KeyFormat::Jwk => serde_json::to_string_pretty(&key.jwk()?).map_err(|e| e.to_string()),
+        KeyFormat::OpenSsh => key.openssh(),
+        KeyFormat::Thumbprint => key.thumbprint(),
+    }
+}
+
+fn read(text: &str) -> Result<Key, String> {
+    if text.starts_with('{') {
+        return from_jwk(text);
+    }
+    if text.starts_with("ssh-") || text.starts_with("ecdsa-sha2-") {
+        return from_openssh(text);
+    }
+    let (label, der) = asn1::input(text)?
+        .into_iter()
+        // written ahead of the key by `openssl ecparam -genkey`
+        .find(|(label, _)| label != "EC PARAMETERS")
+        .ok_or("no key found")?;
+    let nodes = asn1::parse(&der)?;
+    let root = nodes.first().ok_or("empty input")?;
+    match label.as_str() {
+        "RSA PRIVATE KEY" => rsa_private(root),
+        "RSA PUBLIC KEY" => rsa_public(root),
+        "EC PRIVATE KEY" => ec_private(root, None),
+        "PUBLIC KEY" => spki(root),
+        "PRIVATE KEY" => pkcs8(root),
+        "ENCRYPTED PRIVATE KEY" => Err("encrypted private keys are not supported".to_owned()),
+        "" => spki(root)
+            .or_else(|_| pkcs8(root))
+            .or_else(|_| rsa_private(root))
+            .or_else(|_| rsa_public(root))
+            .map_err(|_| "not a SPKI, PKCS#8 or PKCS#1 key".to_owned()),
+        label => Err(format!("unsupported PEM block {}", label)),
+    }
+}
+
+fn expect<'n, 'a>(node: &'n Node<'a>, tag: u32, what: &str) -> Result<&'n Node<'a>, String> {
+    match node.is(tag) {
+        true => Ok(node),
+        false => Err(format!("expected {} at offset {}", what, node.offset)),
+    }
+}
+
+/// magnitude of a non-negative INTEGER, without the sign byte
+fn int(node: &Node, i: usize) -> Result<Vec<u8>, String> {
+    let content = expect(node.child(i)?, INTEGER, "integer")?.content;
+    Ok(magnitude(content).to_vec())
+}
+
+fn magnitude(bytes: &[u8]) -> &[u8] {
+    &bytes[bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len())..]
+}
+
+fn curve_oid(oid: &str) -> Result<&'static Curve, String> {
+    CURVES
+        .iter()
+        .find(|c| c.oid == oid)
+        .ok_or_else(|| format!("unsupported curve {}", asn1::oid_name(oid).unwrap_or(oid)))
+}
+
+fn rsa_private(root: &Node) -> Result<Key, String> {
+    if expect(root, SEQUENCE, "RSAPrivateKey")?.children.len() != 9 {
+        return Err("RSAPrivateKey has nine integers".to_owned());
+    }
+    Ok(Key::Rsa {
+        n: int(root, 1)?,
+        e: int(root, 2)?,
+        private: Some(RsaPrivate {
+            d: int(root, 3)?,
+            p: int(root, 4)?,
+            q: int(root, 5)?,
+            dp: int(root, 6)?,
+            dq: int(root, 7)?,
+            qi: int(root, 8)?,
+        }),
+    })
+}
+
+fn rsa_public(root: &Node) -> Result<Key, String> {
+    if expect(root, SEQUENCE, "RSAPublicKey")?.children.len() != 2 {
+        return Err("RSAPublicKey has two integers".to_owned());
+    }
+    Ok(Key::Rsa {
+        n: int(root, 0)?,
+        e: int(root, 1)?,
+        private: None,
+    })
+}
+
+/// rfc 5915 ECPrivateKey, the curve comes from PKCS#8 or from its own parameters
+fn ec_private(root: &Node, curve: Option<&'static Curve>) -> Result<Key, String> {
+    let d = expect(root.child(1)?, OCTET_STRING, "EC private key")?.content;
+    let params = root.children.iter().find(|n| n.is_context(0));
+    let curve = match (curve, params) {
+        (Some(curve), _) => curve,
+        (None, Some(params)) => curve_oid(&asn1::oid(params.child(0)?.content))?,
+        (None, None) => return Err("EC private key without curve parameters".to_owned()),
+    };
+    // the public key is optional in rfc 5915
+    let point = match root.children.iter().find(|n| n.is_context(1)) {
+        Some(point) => point.child(0)?.bits().to_vec(),
+        None => (curve.public)(&pad(d, curve.size))?,
+    };
+    Ok(Key::Ec {
+        curve,
+        point,
+        d: Some(d.to_vec()),
+    })
+}
+
+fn spki(root: &Node) -> Result<Key, String> {
+    let alg = expect(root.child(0)?, SEQUENCE, "AlgorithmIdentifier")?;
+    let oid = asn1::oid(expect(alg.child(0)?, OID, "key algorithm")?.content);
+    let bits = expect(root.child(1)?, BIT_STRING, "subject public key")?.bits();
+    match oid.as_str() {
+        RSA => rsa_public(asn1::parse(bits)?.first().ok_or("empty RSA key")?),
+        EC => Ok(Key::Ec {
+            curve: curve_oid(&asn1::oid(alg.child(1)?.content))?,
+            point: bits.to_vec(),
+            d: None,
+        }),
+        ED25519 => Ok(Key::Ed25519 {
+            public: ed25519_bytes(bits)?,
+            seed: None,
+        }),
+        oid => Err(format!("unsupported key type {}", asn1::oid_name(oid).unwrap_or(oid))),
+    }
+}
+
+fn pkcs8(root: &Node) -> Result<Key, String> {
+    expect(root.child(0)?, INTEGER, "PrivateKeyInfo version")?;
+    let alg = expect(root.child(1)?, SEQUENCE, "AlgorithmIdentifier")?;
+    let oid = asn1::oid(expect(alg.child(0)?, OID, "key algorithm")?.content);
+    let private = expect(root.child(2)?, OCTET_STRING, "private key")?.content;
+    let inner = asn1::parse(private)?;
+    let inner = inner.first().ok_or("empty private key")?;
+    match oid.as_str() {
+        RSA => rsa_private(inner),
+        EC => ec_private(inner, Some(curve_oid(&asn1::oid(alg.child(1)?.content))?)),
+        ED25519 => {
+            let seed = ed25519_bytes(expect(inner, OCTET_STRING, "Ed25519 seed")?.content)?;
+            // OneAsymmetricKey (rfc 8410 v2) may carry the public key
+            let public = match root.children.iter().find(|n| n.is_context(1)) {
+                Some(public) => ed25519_bytes(public.content.get(1..).unwrap_or_default())?,
+                None => ed25519_dalek::SigningKey::from_bytes(&seed).verifying_key().to_bytes(),
+            };
+            Ok(Key::Ed25519 {
+                public,
+                seed: Some(seed),
+            })
+        },
+        oid => Err(format!("unsupported key type {}", asn1::oid_name(oid).unwrap_or(oid))),
+    }
+}
+
+fn ed25519_bytes(bytes: &[u8]) -> Result<[u8; 32], String> {
+    bytes.try_into().map_err(|_| format!("Ed25519 keys are 32 bytes, not {}", bytes.len()))
+}
+
+fn from_jwk(text: &str) -> Result<Key, String> {
+    let value = serde_json::from_str::<Value>(text).map_err(|e| e.to_string())?;
+    let jwk = match value.get("keys") {
+        Some(Value::Array(keys)) => keys.first().ok_or("JWKS without keys")?,
+        _ => &value,
+    };
+    let text = |name: &str| {
+        jwk.get(name)
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("JWK without {:?}", name))
+    };
+    let field = |name: &str| {
+        URL_SAFE_NO_PAD
+            .decode(text(name)?.trim_end_matches('='))
+            .map_err(|e| format!("{}: {}", name, e))
+    };
+    let private = jwk.get("d").is_some();
+    let crt = ["p", "q", "dp", "dq", "qi"];
+    if private && text("kty")? == "RSA" && crt.iter().any(|k| jwk.get(k).is_none()) {
+        return Err("RSA JWKs with \"d\" need p, q, dp, dq and qi as well".to_owned());
+    }
+    match text("kty")? {
+        "RSA" => Ok(Key::Rsa {
+            n: magnitude(&field("n")?).to_vec(),
+            e: magnitude(&field("e")?).to_vec(),
+            private: match private {
+                true => Some(RsaPrivate {
+                    d: field("d")?,
+                    p: field("p")?,
+                    q: field("q")?,
+                    dp: field("dp")?,
+                    dq: field("dq")?,
+                    qi: field("qi")?,
+                }),
+                false => None,
+            },
+        }),
+        "EC" => {
+            let crv = text("crv")?;
+            let curve = CURVES
+                .iter()
+                .find(|c| c.jwk == crv)
+                .ok_or_else(|| format!("unsupported curve {}", crv))?;
+            let point = [vec![4], pad(&field("x")?, curve.size), pad(&field("y")?, curve.size)];
+            Ok(Key::Ec {
+                curve,
+                point: point.concat(),
+                d: private.then(|| field("d")).transpose()?,
+            })
+        },
+        "OKP" if text("crv")? == "Ed25519" => Ok(Key::Ed25519 {
+            public: ed25519_bytes(&field("x")?)?,
+            seed: private.then(|| ed25519_bytes(&field("d")?)).transpose()?,
+        }),
+        "OKP" => Err(format!("unsupported curve {}", text("crv")?)),
+        kty => Err(format!("unsupported kty {}", kty)),
+    }
+}
+
+fn from_openssh(text: &str) -> Result<Key, String> {
+    let mut parts = text.split_whitespace();
+    let kind = parts.next().unwrap_or_default();
+    let blob = STANDARD
+        .decode(parts.next().ok_or("OpenSSH key without its base64 blob")?)
+        .map_err(|e| e.to_string())?;
+    let mut blob = &blob[..];
+    let mut string = || -> Result<Vec<u8>, String> {
+        let len = blob.get(..4).ok_or("truncated OpenSSH key")?;
+        let len = u32::from_be_bytes(len.try_into().unwrap()) as usize;
+        let value = blob.get(4..4 + len).ok_or("truncated OpenSSH key")?.to_vec();
+        blob = &blob[4 + len..];
+        Ok(value)
+    };
+    if string()? != kind.as_bytes() {
+        return Err(format!("blob does not hold a {} key", kind));
+    }
+    match kind {
+        "ssh-rsa" => {
+            let e = string()?;
+            let n = string()?;
+            Ok(Key::Rsa {
+                n: magnitude(&n).to_vec(),
+                e: magnitude(&e).to_vec(),
+                private: None,
+            })
+        },
+        "ssh-ed25519" => Ok(Key::Ed25519 {
+            public: ed25519_bytes(&string()?)?,
+            seed: None,
+        }),
+        kind => {
+            let ident = String::from_utf8_lossy(&string()?).into_owned();
+            let curve = CURVES
+                .iter()
+                .find(|c| c.ssh == Some(ident.as_str()))
+                .ok_or_else(|| format!("unsupported OpenSSH key type {}", kind))?;
+            Ok(Key::Ec {
+                curve,
+                point: string()?,
+                d: None,
+            })
+        },
+    }
+}
+
+/// left pads big-endian bytes to a fixed width
+fn pad(bytes: &[u8], size: usize) -> Vec<u8> {
+    let bytes = magnitude(bytes);
+    let mut out = vec![0; size.saturating_sub(bytes.len())];
+    out.extend(bytes);
+    out
+}
+
+fn pem(label: &str, der: &[u8]) -> String {
+    let b64 = STANDARD.encode(der);
+    let lines = b64
+        .as_bytes()
+        .chunks(64)
+        .map(|c| String::from_utf8_lossy(c).into_owned())
+        .collect::<Vec<_>>();
+    format!("-----BEGIN {}-----\n{}\n-----END {}-----", label, lines.join("\n"), label)
+}
+
+fn bit_string(bytes: &[u8]) -> Vec<u8> {
+    asn1::tlv(BIT_STRING as u8, &[&[0u8][..], bytes].concat())
+}
+
+fn octet_string(bytes: &[u8]) -> Vec<u8> { asn1::tlv(OCTET_STRING as u8, bytes) }
+
+impl Key {
+    fn coordinates(&self) -> Result<(&[u8], &[u8]), String> {
+        match self {
+            Key::Ec { curve, point, .. } if point.len() == 1 + 2 * curve.size && point[0] == 4 => {
+                Ok(point[1..].split_at(curve.size))
+            },
+            Key::Ec { .. } => Err("only uncompressed EC points are supported".to_owned()),
+            _ => Err("not an EC key".to_owned()),
+        }
+    }
+
+    fn algorithm(&self) -> Vec<u8> {
+        match self {
+            Key::Rsa { .. } => asn1::sequence(&[asn1::encode_oid(RSA), asn1::tlv(5, &[])]),
+            Key::Ec { curve, .. } => {
+                asn1::sequence(&[asn1::encode_oid(EC), asn1::encode_oid(curve.oid)])
+            },
+            Key::Ed25519 { .. } => asn1::sequence(&[asn1::encode_oid(ED25519)]),
+        }
+    }
+
+    fn rsa_public(n: &[u8], e: &[u8]) -> Vec<u8> {
+        asn1::sequence(&[asn1::unsigned(n), asn1::unsigned(e)])
+    }
+
+    /// RSAPrivateKey / RSAPublicKey, or the SEC1 ECPrivateKey for EC keys
+    fn pkcs1(&self) -> Result<(&'static str, Vec<u8>), String> {
+        match self {
+            Key::Rsa {
+                n,
+                e,
+                private: Some(k),
+            } => {
+                let ints: [&[u8]; 9] = [&[0], n, e, &k.d, &k.p, &k.q, &k.dp, &k.dq, &k.qi];
+                let ints = ints.map(asn1::unsigned);
+                Ok(("RSA PRIVATE KEY", asn1::sequence(&ints)))
+            },
+            Key::Rsa { n, e, .. } => Ok(("RSA PUBLIC KEY", Self::rsa_public(n, e))),
+            Key::Ec {
+                curve,
+                point,
+                d: Some(d),
+            } => Ok((
+                "EC PRIVATE KEY",
+                asn1::sequence(&[
+                    asn1::unsigned(&[1]),
+                    octet_string(&pad(d, curve.size)),
+                    asn1::tlv(0xa0, &asn1::encode_oid(curve.oid)),
+                    asn1::tlv(0xa1, &bit_string(point)),
+                ]),
+            )),
+            Key::Ec { .. } => Err("EC public keys are only written as SPKI".to_owned()),
+            Key::Ed25519 { .. } => Err("Ed25519 keys have no PKCS#1 form".to_owned()),
+        }
+    }
+
+    fn pkcs8(&self) -> Result<Vec<u8>, String> {
+        let private = match self {
+            Key::Rsa {
+                private: Some(_), ..
+            } => self.pkcs1()?.1,
+            Key::Ec {
+                curve,
+                point,
+                d: Some(d),
+            } => asn1::sequence(&[
+                asn1::unsigned(&[1]),
+                octet_string(&pad(d, curve.size)),
+                asn1::tlv(0xa1, &bit_string(point)),
+            ]),
+            Key::Ed25519 { seed: Some(s), .. } => octet_string(s),
+            _ => return Err("public keys have no PKCS#8 form, use SPKI".to_owned()),
+        };
+        Ok(asn1::sequence(&[
+            asn1::unsigned(&[0]),
+            self.algorithm(),
+            octet_string(&private),
+        ]))
+    }
+
+    fn spki(&self) -> Result<Vec<u8>, String> {
+        let key = match self {
+            Key::Rsa { n, e, .. } => Self::rsa_public(n, e),
+            Key::Ec { point, .. } => {
+                self.coordinates()?;
+                point.clone()
+            },
+            Key::Ed25519 { public, .. } => public.to_vec(),
+        };
+        Ok(asn1::sequence(&[self.algorithm(), bit_string(&key)]))
+    }
+
+    /// members in rfc 7638 order, so the public part doubles as the thumbprint input
+    fn jwk(&self) -> Result<Value, String> {
+        let b64 = |bytes: &[u8]| Value::from(URL_SAFE_NO_PAD.encode(bytes));
+        let mut jwk = Map::new();
+        match self {
+            Key::Rsa { n, e, private } => {
+                jwk.insert("e".to_owned(), b64(e));
+                jwk.insert("kty".to_owned(), "RSA".into());
+                jwk.insert("n".to_owned(), b64(n));
+                if let Some(k) = private {
+                    let fields = [("d", &k.d), ("p", &k.p), ("q", &k.q)];
+                    let crt = [("dp", &k.dp), ("dq", &k.dq), ("qi", &k.qi)];
+                    for (name, value) in fields.into_iter().chain(crt) {
+                        jwk.insert(name.to_owned(), b64(value));
+                    }
+                }
+            },
+            Key::Ec { curve, d, .. } => {
+                let (x, y) = self.coordinates()?;
+                jwk.insert("crv".to_owned(), curve.jwk.into());
+                jwk.insert("kty".to_owned(), "EC".into());
+                jwk.insert("x".to_owned(), b64(x));
+                jwk.insert("y".to_owned(), b64(y));
+                if let Some(d) = d {
+                    jwk.insert("d".to_owned(), b64(&pad(d, curve.size)));
+                }
+            },
+            Key::Ed25519 { public, seed } => {
+                jwk.insert("crv".to_owned(), "Ed25519".into());
+                jwk.insert("kty".to_owned(), "OKP".into());
+                jwk.insert("x".to_owned(), b64(public));
+                if let Some(seed) = seed {
+                    jwk.insert("d".to_owned(), b64(seed));
+                }
+            },
+        }
+        Ok(Value::Object(jwk))
+    }
+
+    /// rfc 7638, SHA-256 over the required public members
+    fn thumbprint(&self) -> Result<String, String> {
+        let jwk = self.jwk()?;
+        let required = match self {
+            Key::Rsa { .. } => &["e", "kty", "n"][..],
+            Key::Ec { .. } => &["crv", "kty", "x", "y"][..],
+            Key::Ed25519 { .. } => &["crv", "kty", "x"][..],
+        };
+        let members = required.iter().map(|&k| (k.to_owned(), jwk[k].clone()));
+        let canonical = Value::Object(members.collect()).to_string();
+        let digest = digest_bytes(Digest::Sha256, canonical.as_bytes());
+        Ok(format_digest(&digest, DigestFormat::Base64Url))
+    }
+
+    fn openssh(&self) -> Result<String, String> {
+        let mut blob = vec![];
+        let mut string = |bytes: &[u8]| {
+            blob.extend((bytes.len() as u32).to_be_bytes());
+            blob.extend(bytes);
+        };
+        // mpint, with a zero byte ahead of a set high bit
+        let mpint = |bytes: &[u8]| {
+            let bytes = magnitude(bytes);
+            match bytes.first().is_some_and(|b| b & 0x80 != 0) {
+                true => [&[0u8][..], bytes].concat(),
+                false => bytes.to_vec(),
+            }
+        };
+        let kind = match self {
+            Key::Rsa { n, e, .. } => {
+                string(b"ssh-rsa");
+                string(&mpint(e));
+                string(&mpint(n));
+                "ssh-rsa".to_owned()
+            },
+            Key::Ec { curve, point, .. } => {
+                let ident = curve
+                    .ssh
+                    .ok_or_else(|| format!("OpenSSH does not support {}", curve.jwk))?;
+                self.coordinates()?;
+                let kind = format!("ecdsa-sha2-{}", ident);
+                string(kind.as_bytes());
+                string(ident.as_bytes());
+                string(point);
+                kind
+            },
+            Key::Ed25519 { public, .. } => {
+                string(b"ssh-ed25519");
+                string(public);
+                "ssh-ed25519".to_owned()
+            },
+        };
+        Ok(format!("{} {}", kind, STANDARD.encode(blob)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // rfc 7638 section 3.1
+    const RSA_JWK: &str = concat!(
+        r#"{"kty": "RSA", "alg": "RS256", "kid": "2011-04-29", "e": "AQAB", "n": ""#,
+        "0vx7agoebGcQSuuPiLJXZptN9nndrQmbXEps2aiAFbWhM78LhWx4cbbfAAtVT86zwu1RK7aPFFxuhDR1",
+        "L6tSoc_BJECPebWKRXjBZCiFV4n3oknjhMstn64tZ_2W-5JsGY4Hc5n9yBXArwl93lqt7_RN5w6Cf0h4",
+        "QyQ5v-65YGjQR0_FDW2QvzqY368QQMicAtaSqzs8KJZgnYb9c7d0zgdAZHzu6qMQvRL5hajrn1n91CbO",
+        "pbISD08qNLyrdkt-bFTWhAI4vMQFh6WeZu0fM4lFd2NcRwr3XPksINHaQ-G_xBniIqbw0Ls1jF44-csF",
+        "Cur-kEgU8awapJzKnqDKgw",
+        r#""}"#
+    );
+
+    #[test]
+    fn rsa_thumbprint() {
+        assert_eq!(
+            convert(RSA_JWK, KeyFormat::Thumbprint).unwrap(),
+            "NzbLsXh8uDCcd-6MNwXF4W_7noWXFZAfHkxZsRGC9Xs"
+        );
+        // the same key read back from SPKI
+        let spki = convert(RSA_JWK, KeyFormat::Spki).unwrap();
+        assert_eq!(
+            convert(&spki, KeyFormat::Thumbprint).unwrap(),
+            "NzbLsXh8uDCcd-6MNwXF4W_7noWXFZAfHkxZsRGC9Xs"
+        );
+    }
+
+    // rfc 8037 appendix A.3
+    #[test]
+    fn ed25519_thumbprint() {
+        let jwk = r#"{"kty": "OKP", "crv": "Ed25519",
+            "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"}"#;
+        assert_eq!(
+            convert(jwk, KeyFormat::Thumbprint).unwrap(),
+            "kPrK_qmxVWaYVA9wwBF6Iuo3vVzz7TxHCTwXBygrS4k"
+        );
+    }
+}
@@ -1,36 +1,86 @@
 use eframe::egui;
-use egui::TextStyle;
+use egui::{Stroke, TextStyle};
+use std::ops::Range;
 
 #[derive(Default)]
 pub struct LayoutCache {
     style: egui::Style,
     code: String,
+    error: Option<(usize, usize)>,
     output: egui::text::LayoutJob,
 }
 
 impl LayoutCache {
-    pub fn memorise(&mut self, egui_style: &egui::Style, code: &str) -> egui::text::LayoutJob {
-        if (&self.style, self.code.as_str()) != (egui_style, code) {
+    /// error is a 1-based (line, column) position to highlight
+    pub fn memorise(
+        &mut self,
+        egui_style: &egui::Style,
+        code: &str,
+        error: Option<(usize, usize)>,
+    ) -> egui::text::LayoutJob {
+        if (&self.style, self.code.as_str(), self.error) != (egui_style, code, error) {
             self.style = egui_style.clone();
             code.clone_into(&mut self.code);
-            self.output = layout_job(egui_style, code);
+            self.error = error;
+            self.output = layout_job(egui_style, code, error);
         }
         self.output.clone()
     }
 }
 
-pub fn layout_job(egui_style: &egui::Style, text: &str) -> egui::text::LayoutJob {
+pub fn layout_job(
+    egui_style: &egui::Style,
+    text: &str,
+    error: Option<(usize, usize)>,
+) -> egui::text::LayoutJob {
     let mut job = egui::text::LayoutJob::default();
 
     if !text.is_empty() {
-        job.append(text, 0.0, format_from_style(egui_style));
+        let format = format_from_style(egui_style);
+        match error.and_then(|(line, column)| error_range(text, line, column)) {
+            Some(range) => {
+                job.append(&text[..range.start], 0.0, format.clone());
+                job.append(&text[range.clone()], 0.0, error_format(egui_style));
+                job.append(&text[range.end..], 0.0, format);
+            },
+            None => job.append(text, 0.0, format),
+        }
     }
     job
 }
 
+/// from the error column to the end of its line
+fn error_range(text: &str, line: usize, column: usize) -> Option<Range<usize>> {
+    let line_start = text
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum::<usize>();
+    let line_end = line_start + text.get(line_start..)?.split('\n').next()?.len();
+
+    let mut start = (line_start + column.saturating_sub(1)).min(line_end);
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    if start == line_end {
+        // past the end of the line, mark the last character instead
+        start = text[..line_end].char_indices().next_back()?.0;
+    }
+    Some(start..line_end.max(start + 1).min(text.len()))
+}
+
 fn format_from_style(egui_style: &egui::Style) -> egui::text::TextFormat {
     egui::text::TextFormat {
         font_id: TextStyle::Body.resolve(egui_style),
         ..Default::default()
     }
 }
+
+fn error_format(egui_style: &egui::Style) -> egui::text::TextFormat {
+    egui::text::TextFormat {
+        font_id: TextStyle::Body.resolve(egui_style),
+        color: egui_style.visuals.error_fg_color,
+        underline: Stroke::new(1.0, egui_style.visuals.error_fg_color),
+        ..Default::default()
+    }
+}
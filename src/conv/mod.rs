@@ -1,9 +1,31 @@
+mod asn1;
+mod classic;
+mod compress;
 mod converter;
+mod data;
 mod editor;
+mod encoding;
 mod enum_variants;
+mod file_hash;
+mod float;
 mod hasher;
+mod json;
+mod kdf;
+mod keys;
 mod layout_cache;
 mod macros;
+mod number;
+mod pack;
+mod proto;
+mod saml;
+mod serial;
+mod symmetric;
+mod table;
+mod time;
+mod unix_crypt;
+mod x509;
+mod xml;
+mod xor;
 
 pub use converter::convert;
 pub use editor::Editor;
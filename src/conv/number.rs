@@ -0,0 +1,75 @@
+use num_bigint::{BigInt, Sign};
+
+const WIDTHS: [usize; 5] = [8, 16, 32, 64, 128];
+
+pub struct RadixParams {
+    pub from: u32,
+    pub to: u32,
+}
+
+impl Default for RadixParams {
+    fn default() -> Self {
+        Self {
+            from: 10,
+            to: 16,
+        }
+    }
+}
+
+/// the number in the target base, the common bases and two's complement at fixed widths
+pub fn radix(text: &str, params: &RadixParams) -> Result<String, String> {
+    let n = parse(text, params.from)?;
+    let mut lines = vec![n.to_str_radix(params.to), String::new()];
+    let bases = [("bin", 2, "0b"), ("oct", 8, "0o"), ("dec", 10, ""), ("hex", 16, "0x")];
+    for (label, radix, prefix) in bases {
+        let sign = if n.sign() == Sign::Minus { "-" } else { "" };
+        let digits = n.magnitude().to_str_radix(radix).to_uppercase();
+        lines.push(format!("{:<5}{}{}{}", label, sign, prefix, digits));
+    }
+    lines.push(String::new());
+    lines.push("two's complement".to_owned());
+    lines.extend(WIDTHS.iter().map(|&width| twos_complement(&n, width)));
+    Ok(lines.join("\n"))
+}
+
+/// accepts a sign, `_` and whitespace separators, and 0x / 0o / 0b prefixes whose
+/// letter is not a digit of the input base
+pub fn parse(text: &str, radix: u32) -> Result<BigInt, String> {
+    let text = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '_')
+        .collect::<String>();
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(t) => (true, t),
+        None => (false, text.strip_prefix('+').unwrap_or(&text)),
+    };
+    let prefix = text.get(..2).map(str::to_ascii_lowercase);
+    let (radix, digits) = match prefix.as_deref() {
+        Some("0x") if radix <= 33 => (16, &text[2..]),
+        Some("0o") if radix <= 24 => (8, &text[2..]),
+        Some("0b") if radix <= 11 => (2, &text[2..]),
+        _ => (radix, text),
+    };
+    let n = BigInt::parse_bytes(digits.as_bytes(), radix)
+        .ok_or_else(|| format!("{:?} is not a base {} number", digits, radix))?;
+    Ok(if negative { -n } else { n })
+}
+
+fn twos_complement(n: &BigInt, width: usize) -> String {
+    let label = format!("{:>3} bit", width);
+    let modulus = BigInt::from(1) << width;
+    let half = BigInt::from(1) << (width - 1);
+    if *n < -&half || *n >= modulus {
+        return format!("{}  out of range", label);
+    }
+    let bits = if n.sign() == Sign::Minus { n + &modulus } else { n.clone() };
+    let signed = if bits >= half { &bits - &modulus } else { bits.clone() };
+    format!(
+        "{}  0x{:0>digits$}  signed {}  unsigned {}",
+        label,
+        bits.to_str_radix(16).to_uppercase(),
+        signed,
+        bits,
+        digits = width / 4
+    )
+}
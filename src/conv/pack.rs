@@ -0,0 +1,259 @@
+use crate::conv::enum_variants::FloatFormat;
+use crate::conv::{float, number};
+use half::f16;
+use rustc_serialize::hex::{FromHex, ToHex};
+
+/// largest output of pack, a typo in a count should not allocate gigabytes
+const MAX_PACKED: usize = 1 << 20;
+
+#[derive(Copy, Clone, PartialEq)]
+enum Order {
+    Little,
+    Big,
+}
+
+/// one code of the format, `s` keeps its count as the string length
+struct Field {
+    code: char,
+    size: usize,
+}
+
+/// python `struct` style formats with standard sizes and no alignment, ex: '<IHhQ', '>2H4s'
+/// fields past `max` bytes are rejected before they are allocated, `limit` names the maximum
+/// in the error, ex: "the input has"
+fn spec(format: &str, max: usize, limit: &str) -> Result<(Order, Vec<Field>), String> {
+    let format = format.trim();
+    let (order, codes) = match format.chars().next() {
+        Some('<') => (Order::Little, &format[1..]),
+        Some('>' | '!') => (Order::Big, &format[1..]),
+        Some('=' | '@') if cfg!(target_endian = "big") => (Order::Big, &format[1..]),
+        Some('=' | '@') => (Order::Little, &format[1..]),
+        _ => (Order::Little, format),
+    };
+    let mut fields = vec![];
+    let mut total = 0usize;
+    let mut count = String::new();
+    for code in codes.chars().filter(|c| !c.is_whitespace()) {
+        if code.is_ascii_digit() {
+            count.push(code);
+            continue;
+        }
+        let n = match count.as_str() {
+            "" => 1,
+            n => n.parse::<usize>().map_err(|e| e.to_string())?,
+        };
+        count.clear();
+        // a count repeats the field, except for 's' where it is the string length
+        let (size, repeat) = match code {
+            'x' | 'c' | 'b' | 'B' | '?' => (1, n),
+            'h' | 'H' | 'e' => (2, n),
+            'i' | 'I' | 'l' | 'L' | 'f' => (4, n),
+            'q' | 'Q' | 'd' => (8, n),
+            's' => (n, 1),
+            c => return Err(format!("unknown format code {:?}", c)),
+        };
+        total = size
+            .checked_mul(repeat)
+            .and_then(|s| s.checked_add(total))
+            .ok_or_else(|| format!("{:?} is too large", format))?;
+        // keep counting to report the full size, but stop allocating
+        if total <= max {
+            fields.extend((0..repeat).map(|_| Field { code, size }));
+        }
+    }
+    if !count.is_empty() {
+        return Err(format!("count {} without a format code", count));
+    }
+    if total > max {
+        return Err(format!("{:?} needs {} bytes, {} {}", format, total, limit, max));
+    }
+    if fields.is_empty() {
+        return Err("enter a format such as <IHhQ".to_owned());
+    }
+    Ok((order, fields))
+}
+
+/// one line per field with its offset and value
+pub fn unpack(format: &str, bytes: &[u8]) -> Result<String, String> {
+    let (order, fields) = spec(format, bytes.len(), "the input has")?;
+    let mut lines = vec![];
+    let mut offset = 0;
+    for field in fields {
+        let raw = &bytes[offset..offset + field.size];
+        if field.code != 'x' {
+            lines.push(format!(
+                "{:>4}  {}  {}",
+                offset,
+                field.code,
+                read(&field, raw, order)
+            ));
+        }
+        offset += field.size;
+    }
+    if offset < bytes.len() {
+        let rest = &bytes[offset..];
+        lines.push(format!("{} bytes left over: {}", rest.len(), rest.to_hex()));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn read(field: &Field, raw: &[u8], order: Order) -> String {
+    let mut le = raw.to_vec();
+    if order == Order::Big {
+        le.reverse();
+    }
+    let unsigned = le.iter().rev().fold(0u128, |acc, &b| acc << 8 | b as u128);
+    let bits = field.size * 8;
+    match field.code {
+        'c' => format!("{:?}", raw[0] as char),
+        '?' => (raw[0] != 0).to_string(),
+        'b' | 'h' | 'i' | 'l' | 'q' => {
+            // sign extend from the field width
+            let signed = ((unsigned << (128 - bits)) as i128) >> (128 - bits);
+            format!(
+                "{} (0x{:0>digits$x})",
+                signed,
+                unsigned,
+                digits = field.size * 2
+            )
+        },
+        'B' | 'H' | 'I' | 'L' | 'Q' => {
+            format!(
+                "{} (0x{:0>digits$x})",
+                unsigned,
+                unsigned,
+                digits = field.size * 2
+            )
+        },
+        'e' => format!("{:?}", f16::from_bits(unsigned as u16)),
+        'f' => format!("{:?}", f32::from_bits(unsigned as u32)),
+        'd' => format!("{:?}", f64::from_bits(unsigned as u64)),
+        // 's'
+        _ => format!("{:?} ({})", String::from_utf8_lossy(raw), raw.to_hex()),
+    }
+}
+
+/// values separated by whitespace or commas, one per field; `s` takes text or 0x hex
+pub fn pack(format: &str, values: &str) -> Result<Vec<u8>, String> {
+    let (order, fields) = spec(format, MAX_PACKED, "the limit is")?;
+    let mut values = values
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|v| !v.is_empty());
+    let mut out = vec![];
+    for field in fields {
+        if field.code == 'x' {
+            out.push(0);
+            continue;
+        }
+        let value = values
+            .next()
+            .ok_or_else(|| format!("{:?} needs more values", format))?;
+        write(&field, value, order, &mut out).map_err(|e| format!("{}: {}", value, e))?;
+    }
+    if let Some(extra) = values.next() {
+        return Err(format!("{:?} has no field for {}", format, extra));
+    }
+    Ok(out)
+}
+
+fn write(field: &Field, value: &str, order: Order, out: &mut Vec<u8>) -> Result<(), String> {
+    let le = match field.code {
+        'c' => match value.as_bytes() {
+            [b] => vec![*b],
+            _ => return Err("'c' takes a single character".to_owned()),
+        },
+        '?' => match value {
+            "1" | "true" | "True" => vec![1],
+            "0" | "false" | "False" => vec![0],
+            _ => return Err("'?' takes true or false".to_owned()),
+        },
+        'e' => (float::encode(value, FloatFormat::F16)? as u16).to_le_bytes().to_vec(),
+        'f' => value
+            .parse::<f32>()
+            .map_err(|e| e.to_string())?
+            .to_le_bytes()
+            .to_vec(),
+        'd' => value
+            .parse::<f64>()
+            .map_err(|e| e.to_string())?
+            .to_le_bytes()
+            .to_vec(),
+        's' => {
+            let mut bytes = match value.strip_prefix("0x") {
+                Some(hex) => hex.from_hex().map_err(|e| e.to_string())?,
+                None => value.as_bytes().to_vec(),
+            };
+            bytes.resize(field.size, 0);
+            out.extend(bytes);
+            return Ok(());
+        },
+        code => {
+            let n = number::parse(value, 10)?;
+            let n = i128::try_from(&n).map_err(|_| "out of range".to_owned())?;
+            let bits = field.size as u32 * 8;
+            let (min, max) = match code.is_ascii_lowercase() {
+                true => (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1),
+                false => (0, (1i128 << bits) - 1),
+            };
+            if n < min || n > max {
+                return Err(format!("out of range for '{}' ({} to {})", code, min, max));
+            }
+            n.to_le_bytes()[..field.size].to_vec()
+        },
+    };
+    match order {
+        Order::Little => out.extend(le),
+        Order::Big => out.extend(le.iter().rev()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let bytes = pack("<IHhQ", "1, 2, -3, 4").unwrap();
+        assert_eq!(bytes.to_hex(), "010000000200fdff0400000000000000");
+        assert_eq!(
+            unpack("<IHhQ", &bytes).unwrap(),
+            "   0  I  1 (0x00000001)\n   4  H  2 (0x0002)\n   6  h  -3 (0xfffd)\n   8  Q  4 \
+             (0x0000000000000004)"
+        );
+    }
+
+    #[test]
+    fn strings_and_padding() {
+        let bytes = pack(">x3s2xe", "ab 1.5").unwrap();
+        assert_eq!(bytes.to_hex(), "0061620000003e00");
+        assert_eq!(
+            unpack(">x3s2xe", &bytes).unwrap(),
+            "   1  s  \"ab\\0\" (616200)\n   6  e  1.5"
+        );
+        assert_eq!(pack("4s", "0x01020304050607").unwrap(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn half_floats_round_once() {
+        assert_eq!(pack("<e", "65519.99").unwrap(), [0xff, 0x7b]);
+        assert_eq!(pack("<e", "65520").unwrap(), [0x00, 0x7c]);
+        assert_eq!(unpack("<e", &[0xff, 0x7b]).unwrap(), "   0  e  65504.0");
+    }
+
+    #[test]
+    fn range_errors() {
+        assert_eq!(pack("<b", "128").unwrap_err(), "128: out of range for 'b' (-128 to 127)");
+        assert_eq!(pack("<B", "-1").unwrap_err(), "-1: out of range for 'B' (0 to 255)");
+        assert_eq!(pack("<H", "1 2").unwrap_err(), "\"<H\" has no field for 2");
+        assert_eq!(pack("<HH", "1").unwrap_err(), "\"<HH\" needs more values");
+        assert_eq!(
+            unpack("<IHhQ", &[0; 6]).unwrap_err(),
+            "\"<IHhQ\" needs 16 bytes, the input has 6"
+        );
+        assert_eq!(
+            pack("2000000000Q", "").unwrap_err(),
+            "\"2000000000Q\" needs 16000000000 bytes, the limit is 1048576"
+        );
+    }
+}
@@ -0,0 +1,618 @@
+use rustc_serialize::hex::ToHex;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+const MAX_DEPTH: usize = 64;
+
+/// one decoded record of the wire format
+enum Wire<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Len(&'a [u8]),
+    Group(Fields<'a>),
+    Fixed32(u32),
+}
+
+fn varint(buf: &mut &[u8]) -> Result<u64, String> {
+    let mut value = 0u64;
+    for i in 0..10 {
+        let (&b, rest) = buf.split_first().ok_or("truncated varint")?;
+        *buf = rest;
+        value |= ((b & 0x7f) as u64) << (7 * i);
+        if b & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("varint longer than 10 bytes".to_owned())
+}
+
+fn take<'a>(buf: &mut &'a [u8], n: usize) -> Result<&'a [u8], String> {
+    if buf.len() < n {
+        return Err(format!("field needs {} bytes, {} left", n, buf.len()));
+    }
+    let (head, rest) = buf.split_at(n);
+    *buf = rest;
+    Ok(head)
+}
+
+fn fixed64(buf: &mut &[u8]) -> Result<u64, String> {
+    take(buf, 8).map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+}
+
+fn fixed32(buf: &mut &[u8]) -> Result<u32, String> {
+    take(buf, 4).map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+}
+
+type Fields<'a> = Vec<(u64, Wire<'a>)>;
+
+/// parses records until the input ends, or until the end-group tag of `group`
+fn parse<'a>(buf: &mut &'a [u8], group: Option<u64>, depth: usize) -> Result<Fields<'a>, String> {
+    if depth > MAX_DEPTH {
+        return Err("nested too deeply".to_owned());
+    }
+    let mut fields = vec![];
+    while !buf.is_empty() {
+        let tag = varint(buf)?;
+        let number = tag >> 3;
+        if number == 0 {
+            return Err("field number 0".to_owned());
+        }
+        let value = match tag & 7 {
+            0 => Wire::Varint(varint(buf)?),
+            1 => Wire::Fixed64(fixed64(buf)?),
+            2 => {
+                let len = usize::try_from(varint(buf)?).map_err(|e| e.to_string())?;
+                Wire::Len(take(buf, len)?)
+            },
+            3 => Wire::Group(parse(buf, Some(number), depth + 1)?),
+            4 if group == Some(number) => return Ok(fields),
+            4 => return Err(format!("unmatched end of group {}", number)),
+            5 => Wire::Fixed32(fixed32(buf)?),
+            t => return Err(format!("invalid wire type {} for field {}", t, number)),
+        };
+        fields.push((number, value));
+    }
+    match group {
+        Some(number) => Err(format!("group {} is not closed", number)),
+        None => Ok(fields),
+    }
+}
+
+/// decodes without a schema, or names fields after `root` (or the first message) of `schema`
+pub fn decode(
+    bytes: &[u8],
+    schema: Option<&Result<Schema, String>>,
+    root: &str,
+) -> Result<String, String> {
+    let message = match schema {
+        Some(schema) => Some(schema.as_ref().map_err(Clone::clone)?.root(root)?),
+        None => None,
+    };
+    let fields = parse(&mut &bytes[..], None, 0)?;
+    let mut out = String::new();
+    render(&mut out, &fields, message, 0);
+    Ok(out)
+}
+
+fn render(
+    out: &mut String,
+    fields: &[(u64, Wire)],
+    message: Option<(&Schema, &Message)>,
+    depth: usize,
+) {
+    let indent = "  ".repeat(depth);
+    for (number, value) in fields {
+        let def = message.and_then(|(_, m)| m.fields.iter().find(|f| f.number == *number));
+        let _ = match def {
+            Some(def) => write!(out, "{}{} {}: ", indent, number, def.name),
+            None => write!(out, "{}{}: ", indent, number),
+        };
+        match (def, message) {
+            (Some(def), Some((schema, _))) => typed(out, schema, def, value, depth),
+            _ => schemaless(out, value, depth),
+        }
+    }
+}
+
+fn schemaless(out: &mut String, value: &Wire, depth: usize) {
+    let _ = match value {
+        Wire::Varint(v) => {
+            let mut line = format!("varint {}", v);
+            if (*v as i64) < 0 {
+                let _ = write!(line, " (int {})", *v as i64);
+            }
+            if *v != 0 {
+                let _ = write!(line, " (sint {})", zigzag(*v));
+            }
+            writeln!(out, "{}", line)
+        },
+        Wire::Fixed64(v) => writeln!(
+            out,
+            "fixed64 0x{:016x} (int {}, double {})",
+            v,
+            *v as i64,
+            f64::from_bits(*v)
+        ),
+        Wire::Fixed32(v) => writeln!(
+            out,
+            "fixed32 0x{:08x} (int {}, float {})",
+            v,
+            *v as i32,
+            f32::from_bits(*v)
+        ),
+        Wire::Group(fields) => {
+            let _ = writeln!(out, "group {{");
+            render(out, fields, None, depth + 1);
+            writeln!(out, "{}}}", "  ".repeat(depth))
+        },
+        Wire::Len(bytes) => {
+            if let Some(s) = printable(bytes) {
+                return string(out, s);
+            }
+            match parse(&mut &bytes[..], None, depth + 1) {
+                Ok(fields) if !fields.is_empty() => {
+                    let _ = writeln!(out, "message {{");
+                    render(out, &fields, None, depth + 1);
+                    writeln!(out, "{}}}", "  ".repeat(depth))
+                },
+                _ => writeln!(out, "bytes {} ({} bytes)", bytes.to_hex(), bytes.len()),
+            }
+        },
+    };
+}
+
+fn typed(out: &mut String, schema: &Schema, def: &FieldDef, value: &Wire, depth: usize) {
+    let nested = schema.message(&def.ty);
+    let _ = match (value, def.ty.as_str()) {
+        (Wire::Len(bytes), _) if nested.is_some() => match parse(&mut &bytes[..], None, depth + 1) {
+            Ok(fields) => {
+                let _ = writeln!(out, "{} {{", def.ty);
+                render(out, &fields, nested.map(|m| (schema, m)), depth + 1);
+                writeln!(out, "{}}}", "  ".repeat(depth))
+            },
+            Err(e) => writeln!(out, "{} (not a valid message: {})", bytes.to_hex(), e),
+        },
+        (Wire::Group(fields), _) => {
+            let _ = writeln!(out, "group {{");
+            render(out, fields, nested.map(|m| (schema, m)), depth + 1);
+            writeln!(out, "{}}}", "  ".repeat(depth))
+        },
+        (Wire::Len(bytes), "string") => match std::str::from_utf8(bytes) {
+            Ok(s) => return string(out, s),
+            Err(e) => writeln!(out, "string {} (invalid UTF-8: {})", bytes.to_hex(), e),
+        },
+        (Wire::Len(bytes), "bytes") => {
+            writeln!(out, "bytes {} ({} bytes)", bytes.to_hex(), bytes.len())
+        },
+        // a type the file refers to but does not define
+        (Wire::Len(_), ty) if !SCALARS.contains(&ty) && !schema.enums.contains_key(ty) => {
+            return schemaless(out, value, depth);
+        },
+        // packed repeated scalars
+        (Wire::Len(bytes), ty) => {
+            let mut buf = &bytes[..];
+            let mut items = vec![];
+            while !buf.is_empty() {
+                let item = match ty {
+                    "double" | "fixed64" | "sfixed64" => fixed64(&mut buf).map(Wire::Fixed64),
+                    "float" | "fixed32" | "sfixed32" => fixed32(&mut buf).map(Wire::Fixed32),
+                    _ => varint(&mut buf).map(Wire::Varint),
+                };
+                match item {
+                    Ok(item) => items.push(scalar(schema, ty, &item)),
+                    Err(e) => {
+                        let _ = writeln!(out, "packed {} {} ({})", ty, bytes.to_hex(), e);
+                        return;
+                    },
+                }
+            }
+            writeln!(out, "{} [{}]", ty, items.join(", "))
+        },
+        (value, ty) => writeln!(out, "{} {}", ty, scalar(schema, ty, value)),
+    };
+}
+
+fn scalar(schema: &Schema, ty: &str, value: &Wire) -> String {
+    match (value, ty) {
+        (Wire::Varint(v), "int32") => (*v as i32).to_string(),
+        (Wire::Varint(v), "int64") => (*v as i64).to_string(),
+        (Wire::Varint(v), "uint32") => (*v as u32).to_string(),
+        (Wire::Varint(v), "sint32" | "sint64") => zigzag(*v).to_string(),
+        (Wire::Varint(v), "bool") => (*v != 0).to_string(),
+        (Wire::Varint(v), ty) => match schema.enum_value(ty, *v as i32) {
+            Some(name) => format!("{} ({})", name, *v as i32),
+            None => v.to_string(),
+        },
+        (Wire::Fixed64(v), "double") => f64::from_bits(*v).to_string(),
+        (Wire::Fixed64(v), "sfixed64") => (*v as i64).to_string(),
+        (Wire::Fixed64(v), _) => v.to_string(),
+        (Wire::Fixed32(v), "float") => f32::from_bits(*v).to_string(),
+        (Wire::Fixed32(v), "sfixed32") => (*v as i32).to_string(),
+        (Wire::Fixed32(v), _) => v.to_string(),
+        (Wire::Len(bytes), _) => bytes.to_hex(),
+        (Wire::Group(_), _) => "group".to_owned(),
+    }
+}
+
+fn zigzag(v: u64) -> i64 { (v >> 1) as i64 ^ -((v & 1) as i64) }
+
+fn printable(bytes: &[u8]) -> Option<&str> {
+    let s = std::str::from_utf8(bytes).ok()?;
+    let text = s.chars().all(|c| !c.is_control() || matches!(c, '\n' | '\r' | '\t'));
+    (!s.is_empty() && text).then_some(s)
+}
+
+fn string(out: &mut String, s: &str) { let _ = writeln!(out, "string {:?}", s); }
+
+/// the subset of a .proto file needed to name fields: messages, fields and enums
+#[derive(Default)]
+pub struct Schema {
+    pub name: String,
+    /// fully qualified name, in declaration order
+    messages: Vec<(String, Message)>,
+    enums: HashMap<String, Vec<(i32, String)>>,
+}
+
+#[derive(Default)]
+struct Message {
+    fields: Vec<FieldDef>,
+}
+
+struct FieldDef {
+    name: String,
+    number: u64,
+    /// scalar type or fully qualified message / enum name
+    ty: String,
+}
+
+impl Schema {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let mut schema = Self::parse(&text)?;
+        schema.name = name;
+        Ok(schema)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+        };
+        let mut schema = Self::default();
+        let mut package = String::new();
+        while let Some(token) = parser.next() {
+            match token {
+                "syntax" | "edition" | "import" | "option" => parser.skip()?,
+                "package" => {
+                    package = parser.next().ok_or("package name expected")?.to_owned();
+                    parser.expect(";")?;
+                },
+                "message" => parser.message(&mut schema, &package)?,
+                "enum" => parser.enumeration(&mut schema, &package)?,
+                ";" => {},
+                _ => parser.skip()?,
+            }
+        }
+        schema.resolve();
+        Ok(schema)
+    }
+
+    /// the message named by the user, or the first one in the file
+    fn root(&self, name: &str) -> Result<(&Self, &Message), String> {
+        let name = name.trim().trim_start_matches('.');
+        let found = match name {
+            "" => self.messages.first(),
+            _ => self
+                .messages
+                .iter()
+                .find(|(full, _)| full == name || full.ends_with(&format!(".{}", name))),
+        };
+        match found {
+            Some((_, message)) => Ok((self, message)),
+            None => Err(format!("message {:?} not found in {}", name, self.name)),
+        }
+    }
+
+    fn message(&self, name: &str) -> Option<&Message> {
+        self.messages.iter().find(|(full, _)| full == name).map(|(_, m)| m)
+    }
+
+    fn enum_value(&self, name: &str, value: i32) -> Option<&str> {
+        let values = self.enums.get(name)?;
+        values.iter().find(|(v, _)| *v == value).map(|(_, n)| n.as_str())
+    }
+
+    /// rewrites field types to fully qualified names, following protobuf scoping
+    fn resolve(&mut self) {
+        let known = |schema: &Self, name: &str| {
+            schema.messages.iter().any(|(full, _)| full == name) || schema.enums.contains_key(name)
+        };
+        let mut resolved = vec![];
+        for (i, (scope, message)) in self.messages.iter().enumerate() {
+            for (j, field) in message.fields.iter().enumerate() {
+                if SCALARS.contains(&field.ty.as_str()) {
+                    continue;
+                }
+                let ty = match field.ty.strip_prefix('.') {
+                    Some(absolute) => absolute.to_owned(),
+                    None => {
+                        let mut scope = scope.as_str();
+                        loop {
+                            let candidate = match scope {
+                                "" => field.ty.clone(),
+                                _ => format!("{}.{}", scope, field.ty),
+                            };
+                            if known(self, &candidate) || scope.is_empty() {
+                                break candidate;
+                            }
+                            scope = scope.rsplit_once('.').map_or("", |(outer, _)| outer);
+                        }
+                    },
+                };
+                resolved.push((i, j, ty));
+            }
+        }
+        for (i, j, ty) in resolved {
+            self.messages[i].1.fields[j].ty = ty;
+        }
+    }
+}
+
+const SCALARS: [&str; 15] = [
+    "double", "float", "int32", "int64", "uint32", "uint64", "sint32", "sint64", "fixed32",
+    "fixed64", "sfixed32", "sfixed64", "bool", "string", "bytes",
+];
+
+fn tokenize(text: &str) -> Result<Vec<String>, String> {
+    let mut tokens = vec![];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {},
+            '/' if chars.peek() == Some(&'/') => {
+                chars.by_ref().find(|&c| c == '\n');
+            },
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            },
+            '"' | '\'' => {
+                let mut s = String::from(c);
+                loop {
+                    match chars.next().ok_or("unterminated string")? {
+                        '\\' => s.extend(chars.next()),
+                        q if q == c => break,
+                        ch => s.push(ch),
+                    }
+                }
+                tokens.push(s);
+            },
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut s = String::from(c);
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_' || c == '.') {
+                        break;
+                    }
+                    s.push(c);
+                    chars.next();
+                }
+                tokens.push(s);
+            },
+            c => tokens.push(c.to_string()),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [String],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.tokens.get(self.pos)?;
+        self.pos += 1;
+        Some(token)
+    }
+
+    fn expect(&mut self, want: &str) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == want => Ok(()),
+            token => Err(format!(
+                "expected {:?}, found {:?}",
+                want,
+                token.unwrap_or("end of file")
+            )),
+        }
+    }
+
+    /// skips a statement or a braced block
+    fn skip(&mut self) -> Result<(), String> {
+        let mut depth = 0;
+        loop {
+            match self.next().ok_or("unexpected end of file")? {
+                ";" if depth == 0 => return Ok(()),
+                "{" => depth += 1,
+                "}" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+
+    fn message(&mut self, schema: &mut Schema, scope: &str) -> Result<(), String> {
+        let name = qualify(scope, self.next().ok_or("message name expected")?);
+        self.expect("{")?;
+        let index = schema.messages.len();
+        schema.messages.push((name.clone(), Message::default()));
+        loop {
+            let token = self.next().ok_or("unexpected end of file")?;
+            match token {
+                "}" => return Ok(()),
+                ";" => {},
+                "message" => self.message(schema, &name)?,
+                "enum" => self.enumeration(schema, &name)?,
+                "oneof" => {
+                    self.next();
+                    self.expect("{")?;
+                    while self.tokens.get(self.pos).map(String::as_str) != Some("}") {
+                        match self.tokens.get(self.pos).map(String::as_str) {
+                            Some("option") => self.skip()?,
+                            _ => {
+                                let ty = self.next().ok_or("unexpected end of file")?;
+                                let field = self.field(ty)?;
+                                schema.messages[index].1.fields.push(field);
+                            },
+                        }
+                    }
+                    self.next();
+                },
+                "map" => {
+                    self.expect("<")?;
+                    let key = self.next().ok_or("map key type expected")?;
+                    self.expect(",")?;
+                    let value = self.next().ok_or("map value type expected")?;
+                    self.expect(">")?;
+                    let mut field = self.field("")?;
+                    let entry = format!("{}Entry", camel_case(&field.name));
+                    let fields = [("key", 1, key), ("value", 2, value)].map(|(n, i, ty)| FieldDef {
+                        name: n.to_owned(),
+                        number: i,
+                        ty: ty.to_owned(),
+                    });
+                    schema.messages.push((
+                        qualify(&name, &entry),
+                        Message {
+                            fields: fields.into(),
+                        },
+                    ));
+                    field.ty = entry;
+                    schema.messages[index].1.fields.push(field);
+                },
+                "option" | "reserved" | "extensions" | "extend" => self.skip()?,
+                "repeated" | "optional" | "required" => {
+                    let ty = self.next().ok_or("field type expected")?;
+                    let field = self.field(ty)?;
+                    schema.messages[index].1.fields.push(field);
+                },
+                ty => {
+                    let field = self.field(ty)?;
+                    schema.messages[index].1.fields.push(field);
+                },
+            }
+        }
+    }
+
+    /// `name = number [options];` after the type
+    fn field(&mut self, ty: &str) -> Result<FieldDef, String> {
+        let name = self.next().ok_or("field name expected")?.to_owned();
+        self.expect("=")?;
+        let number = self.next().ok_or("field number expected")?;
+        let number = number
+            .parse()
+            .map_err(|_| format!("invalid field number {:?} for {}", number, name))?;
+        self.skip()?;
+        Ok(FieldDef {
+            name,
+            number,
+            ty: ty.to_owned(),
+        })
+    }
+
+    fn enumeration(&mut self, schema: &mut Schema, scope: &str) -> Result<(), String> {
+        let name = qualify(scope, self.next().ok_or("enum name expected")?);
+        self.expect("{")?;
+        let mut values = vec![];
+        loop {
+            match self.next().ok_or("unexpected end of file")? {
+                "}" => break,
+                ";" => {},
+                "option" | "reserved" => self.skip()?,
+                value => {
+                    self.expect("=")?;
+                    let number = self.next().ok_or("enum value expected")?;
+                    let number = number
+                        .parse()
+                        .map_err(|_| format!("invalid enum value {:?} for {}", number, value))?;
+                    values.push((number, value.to_owned()));
+                    self.skip()?;
+                },
+            }
+        }
+        schema.enums.insert(name, values);
+        Ok(())
+    }
+}
+
+fn qualify(scope: &str, name: &str) -> String {
+    match scope {
+        "" => name.to_owned(),
+        _ => format!("{}.{}", scope, name),
+    }
+}
+
+/// map field `string_map` gets the entry message `StringMapEntry`
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn varints() {
+        assert_eq!(varint(&mut &[0x96, 0x01][..]), Ok(150));
+        assert_eq!(varint(&mut &[0xac, 0x02][..]), Ok(300));
+        let max = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+        assert_eq!(varint(&mut &max[..]), Ok(u64::MAX));
+        assert_eq!(varint(&mut &[0x80][..]), Err("truncated varint".to_owned()));
+        assert_eq!(varint(&mut &[0x80; 11][..]), Err("varint longer than 10 bytes".to_owned()));
+    }
+
+    #[test]
+    fn zigzags() {
+        assert_eq!(zigzag(0), 0);
+        assert_eq!(zigzag(1), -1);
+        assert_eq!(zigzag(2), 1);
+        assert_eq!(zigzag(4294967294), 2147483647);
+        assert_eq!(zigzag(4294967295), -2147483648);
+        assert_eq!(zigzag(u64::MAX), i64::MIN);
+    }
+
+    #[test]
+    fn decode_with_and_without_schema() {
+        let bytes = [0x08, 0x96, 0x01, 0x10, 0x03];
+        assert_eq!(
+            decode(&bytes, None, "").unwrap(),
+            "1: varint 150 (sint 75)\n2: varint 3 (sint -2)\n"
+        );
+        let schema = Schema::parse("message Test { int32 a = 1; sint32 b = 2; }");
+        assert_eq!(
+            decode(&bytes, Some(&schema), "Test").unwrap(),
+            "1 a: int32 150\n2 b: sint32 -2\n"
+        );
+    }
+}
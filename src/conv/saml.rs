@@ -0,0 +1,322 @@
+use crate::conv::compress;
+use crate::conv::encoding;
+use crate::conv::enum_variants::{Codec, Encoding};
+use crate::conv::time::{parse_time, unix_now};
+use crate::conv::xml;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+const SAML_MESSAGES: [&str; 2] = ["SAMLRequest", "SAMLResponse"];
+
+const BINDING_PARAMS: [&str; 5] =
+    ["SAMLRequest", "SAMLResponse", "RelayState", "SigAlg", "Signature"];
+
+const ROOT_ATTRS: [&str; 4] = ["ID", "IssueInstant", "Destination", "InResponseTo"];
+
+/// allowed difference between our clock and the idp clock, in seconds
+const CLOCK_SKEW: i64 = 180;
+
+struct Message {
+    name: String,
+    params: Vec<(String, String)>,
+    data: Vec<u8>,
+}
+
+/// HTTP-Redirect binding: url decode, base64, inflate
+pub fn decode_redirect(input: &str) -> Result<String, String> {
+    let msg = message(input)?;
+    let xml = inflate(&msg.data)?;
+    Ok(render(&msg, xml))
+}
+
+/// HTTP-POST binding: url decode, base64
+pub fn decode_post(input: &str) -> Result<String, String> {
+    let msg = message(input)?;
+    let xml = String::from_utf8_lossy(&msg.data).into_owned();
+    Ok(render(&msg, xml))
+}
+
+/// structured view of a SAML message, accepts xml or either binding
+pub fn inspect(input: &str) -> Result<String, String> {
+    let input = input.trim();
+    let xml = match input.starts_with('<') {
+        true => input.to_owned(),
+        false => {
+            let msg = message(input)?;
+            match String::from_utf8(msg.data.clone()) {
+                Ok(xml) if xml.trim_start().starts_with('<') => xml,
+                _ => inflate(&msg.data)?,
+            }
+        },
+    };
+    Inspection::parse(&xml).map(|x| x.render(unix_now()))
+}
+
+fn message(input: &str) -> Result<Message, String> {
+    let params = query_params(input);
+    let (name, message) = match params
+        .iter()
+        .find(|(k, _)| SAML_MESSAGES.contains(&k.as_str()))
+    {
+        Some((k, v)) => (k.to_owned(), v.as_str()),
+        // a bare base64 message without the form layout
+        None => ("SAMLMessage".to_owned(), input.trim()),
+    };
+    let data = encoding::decode(message, Encoding::Base64)?;
+    let params = params
+        .iter()
+        .filter(|(k, _)| !SAML_MESSAGES.contains(&k.as_str()))
+        .cloned()
+        .collect();
+    Ok(Message { name, params, data })
+}
+
+fn inflate(data: &[u8]) -> Result<String, String> {
+    let xml = compress::decompress(Codec::Deflate, data)?;
+    Ok(String::from_utf8_lossy(&xml).into_owned())
+}
+
+fn render(msg: &Message, xml: String) -> String {
+    let mut lines = vec![msg.name.clone()];
+    lines.extend(msg.params.iter().map(|(k, v)| format!("{}: {}", k, v)));
+    lines.push(String::new());
+    lines.push(xml::pretty(&xml, &Default::default()).unwrap_or(xml));
+    lines.join("\n")
+}
+
+/// query string of a redirect url or a form body, '+' is kept as base64 uses it
+fn query_params(input: &str) -> Vec<(String, String)> {
+    let input = input.trim();
+    let query = input.split_once('?').map_or(input, |(_, q)| q);
+    let query = query.split_once('#').map_or(query, |(q, _)| q);
+    let params = query
+        .split('&')
+        .filter_map(|x| x.split_once('='))
+        .map(|(k, v)| (url_escape::decode(k).into_owned(), url_escape::decode(v).into_owned()))
+        .collect::<Vec<_>>();
+    // the '=' of a bare base64 message is padding, not a parameter
+    let is_query = input.contains(['?', '&'])
+        || params.iter().any(|(k, _)| BINDING_PARAMS.contains(&k.as_str()));
+    match is_query {
+        true => params,
+        false => vec![],
+    }
+}
+
+#[derive(Default)]
+struct Inspection {
+    root: String,
+    root_attrs: Vec<(String, String)>,
+    root_issuer: Option<String>,
+    root_signed: bool,
+    status: Option<String>,
+    assertions: usize,
+    encrypted_assertions: usize,
+    assertion_issuer: Option<String>,
+    assertion_signed: bool,
+    name_id: Option<String>,
+    name_id_format: Option<String>,
+    audiences: Vec<String>,
+    not_before: Option<String>,
+    not_on_or_after: Option<String>,
+    attributes: Vec<(String, Vec<String>)>,
+}
+
+impl Inspection {
+    fn parse(xml: &str) -> Result<Self, String> {
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().trim_text(true);
+        let mut x = Self::default();
+        let mut path: Vec<String> = vec![];
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) => {
+                    let name = local_name(&e);
+                    x.start(&path, &name, &e);
+                    path.push(name);
+                },
+                Ok(Event::Empty(e)) => x.start(&path, &local_name(&e), &e),
+                Ok(Event::End(_)) => {
+                    path.pop();
+                },
+                Ok(Event::Text(t)) => {
+                    let text = t.unescape().map_err(|e| e.to_string())?;
+                    x.text(&path, &text);
+                },
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(format!("position {}: {}", reader.error_position(), e)),
+                _ => {},
+            }
+        }
+        if x.root.is_empty() {
+            return Err("no xml element found".to_owned());
+        }
+        Ok(x)
+    }
+
+    fn start(&mut self, path: &[String], name: &str, e: &BytesStart) {
+        let parent = path.last().map(String::as_str);
+        match (parent, name) {
+            (None, _) => {
+                self.root = name.to_owned();
+                self.root_attrs = attrs(e)
+                    .into_iter()
+                    .filter(|(k, _)| ROOT_ATTRS.contains(&k.as_str()))
+                    .collect();
+                if name == "Assertion" {
+                    self.assertions += 1;
+                }
+            },
+            (Some(parent), "Signature") => {
+                self.root_signed |= path.len() == 1;
+                self.assertion_signed |= parent == "Assertion";
+            },
+            (Some(_), "Assertion") => self.assertions += 1,
+            (Some(_), "EncryptedAssertion") => self.encrypted_assertions += 1,
+            (Some("Status"), "StatusCode") => {
+                self.status = self.status.take().or_else(|| attr(e, "Value"));
+            },
+            (Some("Subject"), "NameID") => self.name_id_format = attr(e, "Format"),
+            (Some(_), "Conditions") => {
+                self.not_before = attr(e, "NotBefore");
+                self.not_on_or_after = attr(e, "NotOnOrAfter");
+            },
+            (Some("AttributeStatement"), "Attribute") => {
+                let name = attr(e, "FriendlyName")
+                    .or_else(|| attr(e, "Name"))
+                    .unwrap_or_default();
+                self.attributes.push((name, vec![]));
+            },
+            _ => {},
+        }
+    }
+
+    fn text(&mut self, path: &[String], text: &str) {
+        let n = path.len();
+        let parent = match n {
+            0 | 1 => None,
+            _ => Some(path[n - 2].as_str()),
+        };
+        match (parent, path.last().map(String::as_str)) {
+            (parent, Some("Issuer")) => {
+                if n == 2 {
+                    self.root_issuer = Some(text.to_owned());
+                }
+                if parent == Some("Assertion") {
+                    self.assertion_issuer = Some(text.to_owned());
+                }
+            },
+            (Some("Subject"), Some("NameID")) => self.name_id = Some(text.to_owned()),
+            (_, Some("Audience")) => self.audiences.push(text.to_owned()),
+            (Some("Attribute"), Some("AttributeValue")) => {
+                if let Some((_, values)) = self.attributes.last_mut() {
+                    values.push(text.to_owned());
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fn render(&self, now: i64) -> String {
+        let mut lines = vec![self.root.clone()];
+        let mut warnings = vec![];
+        lines.extend(self.root_attrs.iter().map(|(k, v)| format!("  {}: {}", k, v)));
+        if let Some(status) = &self.status {
+            lines.push(format!("  Status: {}", status.rsplit(':').next().unwrap_or(status)));
+        }
+        lines.push(format!("  Issuer: {}", self.root_issuer.as_deref().unwrap_or("-")));
+        lines.push(format!("  Signature: {}", present(self.root_signed)));
+
+        if self.encrypted_assertions > 0 {
+            warnings.push(format!(
+                "{} encrypted assertion(s) cannot be inspected",
+                self.encrypted_assertions
+            ));
+        }
+        if self.assertions > 0 {
+            lines.push("Assertion".to_owned());
+            lines.push(format!("  Issuer: {}", self.assertion_issuer.as_deref().unwrap_or("-")));
+            lines.push(format!("  Signature: {}", present(self.assertion_signed)));
+            lines.push(format!("  NameID: {}", self.name_id.as_deref().unwrap_or("-")));
+            if let Some(format) = &self.name_id_format {
+                lines.push(format!("  NameID Format: {}", format));
+            }
+            for audience in &self.audiences {
+                lines.push(format!("  Audience: {}", audience));
+            }
+            if let Some(t) = &self.not_before {
+                lines.push(format!("  NotBefore: {}", t));
+                match parse_time(t) {
+                    Some(t) if now + CLOCK_SKEW < t => warnings.push(format!(
+                        "not yet valid, NotBefore is {}s in the future",
+                        t - now
+                    )),
+                    Some(t) if now < t => warnings.push(format!(
+                        "NotBefore is {}s ahead, within the {}s clock skew",
+                        t - now,
+                        CLOCK_SKEW
+                    )),
+                    Some(_) => {},
+                    None => warnings.push(format!("unparsable NotBefore: {}", t)),
+                }
+            }
+            if let Some(t) = &self.not_on_or_after {
+                lines.push(format!("  NotOnOrAfter: {}", t));
+                match parse_time(t) {
+                    Some(t) if now >= t + CLOCK_SKEW => {
+                        warnings.push(format!("expired {}s ago", now - t))
+                    },
+                    Some(t) if now >= t => warnings.push(format!(
+                        "NotOnOrAfter passed {}s ago, within the {}s clock skew",
+                        now - t,
+                        CLOCK_SKEW
+                    )),
+                    Some(_) => {},
+                    None => warnings.push(format!("unparsable NotOnOrAfter: {}", t)),
+                }
+            }
+            if !self.root_signed && !self.assertion_signed {
+                warnings.push("neither the message nor the assertion is signed".to_owned());
+            }
+        }
+        if !self.attributes.is_empty() {
+            lines.push("Attributes".to_owned());
+            lines.extend(
+                self.attributes
+                    .iter()
+                    .map(|(k, v)| format!("  {}: {}", k, v.join(", "))),
+            );
+        }
+        if !warnings.is_empty() {
+            lines.push("Warnings".to_owned());
+            lines.extend(warnings.iter().map(|w| format!("  ⚠ {}", w)));
+        }
+        lines.join("\n")
+    }
+}
+
+fn local_name(e: &BytesStart) -> String {
+    String::from_utf8_lossy(e.local_name().as_ref()).into_owned()
+}
+
+fn attrs(e: &BytesStart) -> Vec<(String, String)> {
+    e.attributes()
+        .flatten()
+        .map(|a| {
+            let key = String::from_utf8_lossy(a.key.local_name().as_ref()).into_owned();
+            let value = a.unescape_value().map(|v| v.into_owned()).unwrap_or_default();
+            (key, value)
+        })
+        .collect()
+}
+
+fn attr(e: &BytesStart, name: &str) -> Option<String> {
+    attrs(e).into_iter().find(|(k, _)| k == name).map(|(_, v)| v)
+}
+
+fn present(b: bool) -> &'static str {
+    match b {
+        true => "present",
+        false => "absent",
+    }
+}
@@ -0,0 +1,327 @@
+use crate::conv::data::DataError;
+use crate::conv::enum_variants::BinaryFormat;
+use rustc_serialize::hex::{FromHex, ToHex};
+use serde_json::{json, Map, Number, Value};
+
+/// values without a JSON equivalent become annotation objects: `{"$bytes": "<hex>"}`,
+/// `{"$tag": n, "$value": v}` (CBOR), `{"$ext": type, "$data": "<hex>"}` (MessagePack),
+/// `{"$int": "<decimal>"}`, `{"$float": "NaN"}` and `{"$map": [[key, value], ...]}`
+/// for maps with non-string keys. BSON uses MongoDB canonical Extended JSON instead.
+/// Round trips keep values, not bytes: integers are encoded in their shortest form, CBOR
+/// floats in the shortest exact width and MessagePack floats as f64, even if read as f32.
+pub fn decode(format: BinaryFormat, bytes: &[u8]) -> Result<String, String> {
+    let value = match format {
+        BinaryFormat::MessagePack => {
+            let mut rd = bytes;
+            let value = rmpv::decode::read_value(&mut rd).map_err(|e| e.to_string())?;
+            trailing(rd.len())?;
+            from_msgpack(value)
+        },
+        BinaryFormat::Cbor => {
+            let mut rd = bytes;
+            let value = ciborium::de::from_reader::<ciborium::Value, _>(&mut rd)
+                .map_err(|e| e.to_string())?;
+            trailing(rd.len())?;
+            from_cbor(value)
+        },
+        BinaryFormat::Bson => {
+            let doc = bson::Document::from_reader(bytes).map_err(|e| e.to_string())?;
+            bson::Bson::Document(doc).into_canonical_extjson()
+        },
+    };
+    serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
+}
+
+/// the reverse of `decode`, annotation objects are turned back into their types
+pub fn encode(format: BinaryFormat, text: &str) -> Result<Vec<u8>, DataError> {
+    let value = serde_json::from_str::<Value>(text)?;
+    let mut buf = vec![];
+    match format {
+        BinaryFormat::MessagePack => {
+            rmpv::encode::write_value(&mut buf, &to_msgpack(value)?).map_err(error)?
+        },
+        BinaryFormat::Cbor => {
+            ciborium::ser::into_writer(&to_cbor(value)?, &mut buf).map_err(error)?
+        },
+        BinaryFormat::Bson => match bson::Bson::try_from(value).map_err(error)? {
+            bson::Bson::Document(doc) => doc.to_writer(&mut buf).map_err(error)?,
+            _ => return Err(error("BSON documents must be objects")),
+        },
+    }
+    Ok(buf)
+}
+
+fn from_msgpack(value: rmpv::Value) -> Value {
+    use rmpv::Value as Mp;
+    match value {
+        Mp::Nil => Value::Null,
+        Mp::Boolean(b) => Value::Bool(b),
+        Mp::Integer(i) => match (i.as_i64(), i.as_u64()) {
+            (Some(i), _) => Value::from(i),
+            (_, Some(u)) => Value::from(u),
+            _ => json!({ "$int": i.to_string() }),
+        },
+        Mp::F32(f) => float(f as f64),
+        Mp::F64(f) => float(f),
+        Mp::String(s) => match s.as_str() {
+            Some(s) => Value::String(s.to_owned()),
+            None => json!({ "$invalid_utf8": s.as_bytes().to_hex() }),
+        },
+        Mp::Binary(b) => json!({ "$bytes": b.to_hex() }),
+        Mp::Array(a) => Value::Array(a.into_iter().map(from_msgpack).collect()),
+        Mp::Map(m) => map(m.into_iter().map(|(k, v)| (from_msgpack(k), from_msgpack(v)))),
+        Mp::Ext(t, data) => json!({ "$ext": t, "$data": data.to_hex() }),
+    }
+}
+
+fn from_cbor(value: ciborium::Value) -> Value {
+    use ciborium::Value as Cb;
+    match value {
+        Cb::Null => Value::Null,
+        Cb::Bool(b) => Value::Bool(b),
+        Cb::Integer(i) => {
+            let i = i128::from(i);
+            match (i64::try_from(i), u64::try_from(i)) {
+                (Ok(i), _) => Value::from(i),
+                (_, Ok(u)) => Value::from(u),
+                _ => json!({ "$int": i.to_string() }),
+            }
+        },
+        Cb::Float(f) => float(f),
+        Cb::Text(s) => Value::String(s),
+        Cb::Bytes(b) => json!({ "$bytes": b.to_hex() }),
+        Cb::Array(a) => Value::Array(a.into_iter().map(from_cbor).collect()),
+        Cb::Map(m) => map(m.into_iter().map(|(k, v)| (from_cbor(k), from_cbor(v)))),
+        Cb::Tag(tag, v) => json!({ "$tag": tag, "$value": from_cbor(*v) }),
+        v => json!({ "$unknown": format!("{:?}", v) }),
+    }
+}
+
+fn float(f: f64) -> Value {
+    match Number::from_f64(f) {
+        Some(n) => Value::Number(n),
+        None => json!({ "$float": f.to_string() }),
+    }
+}
+
+/// objects when every key is a string, `$map` pairs otherwise
+fn map(pairs: impl Iterator<Item = (Value, Value)>) -> Value {
+    let pairs = pairs.collect::<Vec<_>>();
+    if pairs.iter().all(|(k, _)| k.is_string()) {
+        return Value::Object(
+            pairs
+                .into_iter()
+                .map(|(k, v)| (k.as_str().unwrap_or_default().to_owned(), v))
+                .collect(),
+        );
+    }
+    json!({ "$map": pairs.into_iter().map(|(k, v)| json!([k, v])).collect::<Vec<_>>() })
+}
+
+/// the annotation objects, or None for an ordinary object
+enum Annotated<'a> {
+    Bytes(Vec<u8>),
+    Int(i128),
+    Float(f64),
+    Tag(u64, &'a Value),
+    Ext(i8, Vec<u8>),
+    Map(&'a Vec<Value>),
+}
+
+fn annotation(o: &Map<String, Value>) -> Result<Option<Annotated<'_>>, DataError> {
+    let hex = |key: &str| {
+        o[key]
+            .as_str()
+            .ok_or_else(|| error(format!("{} must be a hex string", key)))?
+            .from_hex()
+            .map_err(|e| error(e.to_string()))
+    };
+    let keys = o.keys().map(String::as_str).collect::<Vec<_>>();
+    Ok(Some(match keys.as_slice() {
+        ["$bytes"] => Annotated::Bytes(hex("$bytes")?),
+        ["$int"] => Annotated::Int(
+            o["$int"]
+                .as_str()
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(|| error("$int must be a decimal string"))?,
+        ),
+        ["$float"] => Annotated::Float(
+            o["$float"]
+                .as_str()
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(|| error("$float must be a float string"))?,
+        ),
+        ["$tag", "$value"] | ["$value", "$tag"] => Annotated::Tag(
+            o["$tag"]
+                .as_u64()
+                .ok_or_else(|| error("$tag must be an unsigned integer"))?,
+            &o["$value"],
+        ),
+        ["$ext", "$data"] | ["$data", "$ext"] => Annotated::Ext(
+            o["$ext"]
+                .as_i64()
+                .and_then(|t| i8::try_from(t).ok())
+                .ok_or_else(|| error("$ext must be an i8"))?,
+            hex("$data")?,
+        ),
+        ["$map"] => Annotated::Map(
+            o["$map"]
+                .as_array()
+                .ok_or_else(|| error("$map must be an array of pairs"))?,
+        ),
+        _ => return Ok(None),
+    }))
+}
+
+fn pair(v: &Value) -> Result<(&Value, &Value), DataError> {
+    match v.as_array().map(Vec::as_slice) {
+        Some([k, v]) => Ok((k, v)),
+        _ => Err(error("$map entries must be [key, value] pairs")),
+    }
+}
+
+fn to_msgpack(value: Value) -> Result<rmpv::Value, DataError> {
+    use rmpv::Value as Mp;
+    Ok(match value {
+        Value::Null => Mp::Nil,
+        Value::Bool(b) => Mp::Boolean(b),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => Mp::from(i),
+            (_, Some(u)) => Mp::from(u),
+            _ => Mp::F64(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => Mp::String(s.into()),
+        Value::Array(a) => Mp::Array(a.into_iter().map(to_msgpack).collect::<Result<_, _>>()?),
+        Value::Object(o) => match annotation(&o)? {
+            Some(Annotated::Bytes(b)) => Mp::Binary(b),
+            Some(Annotated::Int(i)) => match (i64::try_from(i), u64::try_from(i)) {
+                (Ok(i), _) => Mp::from(i),
+                (_, Ok(u)) => Mp::from(u),
+                _ => return Err(error("integer out of range for MessagePack")),
+            },
+            Some(Annotated::Float(f)) => Mp::F64(f),
+            Some(Annotated::Ext(t, data)) => Mp::Ext(t, data),
+            Some(Annotated::Map(pairs)) => Mp::Map(
+                pairs
+                    .iter()
+                    .map(|p| {
+                        let (k, v) = pair(p)?;
+                        Ok((to_msgpack(k.clone())?, to_msgpack(v.clone())?))
+                    })
+                    .collect::<Result<_, DataError>>()?,
+            ),
+            Some(Annotated::Tag(..)) => return Err(error("MessagePack has no tags")),
+            None => Mp::Map(
+                o.into_iter()
+                    .map(|(k, v)| Ok((Mp::String(k.into()), to_msgpack(v)?)))
+                    .collect::<Result<_, DataError>>()?,
+            ),
+        },
+    })
+}
+
+fn to_cbor(value: Value) -> Result<ciborium::Value, DataError> {
+    use ciborium::Value as Cb;
+    Ok(match value {
+        Value::Null => Cb::Null,
+        Value::Bool(b) => Cb::Bool(b),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => Cb::Integer(i.into()),
+            (_, Some(u)) => Cb::Integer(u.into()),
+            _ => Cb::Float(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => Cb::Text(s),
+        Value::Array(a) => Cb::Array(a.into_iter().map(to_cbor).collect::<Result<_, _>>()?),
+        Value::Object(o) => match annotation(&o)? {
+            Some(Annotated::Bytes(b)) => Cb::Bytes(b),
+            Some(Annotated::Int(i)) => Cb::Integer(
+                ciborium::value::Integer::try_from(i)
+                    .map_err(|_| error("integer out of range for CBOR"))?,
+            ),
+            Some(Annotated::Float(f)) => Cb::Float(f),
+            Some(Annotated::Tag(tag, v)) => Cb::Tag(tag, Box::new(to_cbor(v.clone())?)),
+            Some(Annotated::Map(pairs)) => Cb::Map(
+                pairs
+                    .iter()
+                    .map(|p| {
+                        let (k, v) = pair(p)?;
+                        Ok((to_cbor(k.clone())?, to_cbor(v.clone())?))
+                    })
+                    .collect::<Result<_, DataError>>()?,
+            ),
+            Some(Annotated::Ext(..)) => return Err(error("CBOR has no ext types")),
+            None => Cb::Map(
+                o.into_iter()
+                    .map(|(k, v)| Ok((Cb::Text(k), to_cbor(v)?)))
+                    .collect::<Result<_, DataError>>()?,
+            ),
+        },
+    })
+}
+
+fn trailing(n: usize) -> Result<(), String> {
+    match n {
+        0 => Ok(()),
+        n => Err(format!("{} trailing bytes after the first value", n)),
+    }
+}
+
+fn error(e: impl ToString) -> DataError {
+    DataError {
+        message: e.to_string(),
+        position: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(format: BinaryFormat, hex: &str, expected: Value) {
+        let bytes = hex.from_hex().unwrap();
+        let text = decode(format, &bytes).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&text).unwrap(), expected);
+        assert_eq!(encode(format, &text).map_err(|e| e.message).unwrap().to_hex(), hex);
+    }
+
+    #[test]
+    fn cbor_annotations() {
+        // tag 1 time, bytes, -2^64, NaN and a map with integer keys
+        round_trip(
+            BinaryFormat::Cbor,
+            "85c11a514b67b044010203043bfffffffffffffffff97e00a201020304",
+            json!([
+                { "$tag": 1, "$value": 1363896240 },
+                { "$bytes": "01020304" },
+                { "$int": "-18446744073709551616" },
+                { "$float": "NaN" },
+                { "$map": [[1, 2], [3, 4]] },
+            ]),
+        );
+    }
+
+    #[test]
+    fn msgpack_annotations() {
+        // fixext 1, bin 8 and a map with an integer key
+        round_trip(
+            BinaryFormat::MessagePack,
+            "93d401ffc402abcd810102",
+            json!([{ "$ext": 1, "$data": "ff" }, { "$bytes": "abcd" }, { "$map": [[1, 2]] }]),
+        );
+        // an f32 comes back as an f64 of the same value
+        let text = decode(BinaryFormat::MessagePack, &[0xca, 0x3f, 0xc0, 0, 0]).unwrap();
+        assert_eq!(text, "1.5");
+        let bytes = encode(BinaryFormat::MessagePack, &text).map_err(|e| e.message).unwrap();
+        assert_eq!(bytes.to_hex(), "cb3ff8000000000000");
+    }
+
+    #[test]
+    fn invalid_annotations() {
+        let message = |text| encode(BinaryFormat::Cbor, text).unwrap_err().message;
+        assert_eq!(message(r#"{"$bytes": 1}"#), "$bytes must be a hex string");
+        assert_eq!(message(r#"{"$int": "x"}"#), "$int must be a decimal string");
+        let text = r#"{"$ext": 1, "$data": []}"#;
+        let message = encode(BinaryFormat::MessagePack, text).unwrap_err().message;
+        assert_eq!(message, "$data must be a hex string");
+    }
+}
@@ -0,0 +1,127 @@
+use crate::conv::enum_variants::{BlockMode, SymmetricCipher};
+use aes::cipher::block_padding::Pkcs7;
+use aes::cipher::consts::{U12, U16};
+use aes::cipher::{
+    BlockCipher, BlockDecrypt, BlockDecryptMut, BlockEncrypt, BlockEncryptMut, BlockSizeUser,
+    KeyInit, KeyIvInit, StreamCipher,
+};
+use aes_gcm::aead::generic_array::typenum::Unsigned;
+use aes_gcm::aead::{Aead, Nonce, Payload};
+use aes_gcm::AesGcm;
+use chacha20poly1305::ChaCha20Poly1305;
+
+pub struct Params<'a> {
+    pub key: &'a [u8],
+    pub iv: &'a [u8],
+    pub aad: &'a [u8],
+}
+
+pub fn encrypt(
+    cipher: SymmetricCipher,
+    mode: BlockMode,
+    params: &Params,
+    data: &[u8],
+) -> Result<Vec<u8>, String> {
+    crypt(cipher, mode, params, data, false)
+}
+
+pub fn decrypt(
+    cipher: SymmetricCipher,
+    mode: BlockMode,
+    params: &Params,
+    data: &[u8],
+) -> Result<Vec<u8>, String> {
+    crypt(cipher, mode, params, data, true)
+}
+
+fn crypt(
+    cipher: SymmetricCipher,
+    mode: BlockMode,
+    params: &Params,
+    data: &[u8],
+    decrypt: bool,
+) -> Result<Vec<u8>, String> {
+    match cipher {
+        SymmetricCipher::Aes128 => aes_crypt::<aes::Aes128>(mode, params, data, decrypt),
+        SymmetricCipher::Aes192 => aes_crypt::<aes::Aes192>(mode, params, data, decrypt),
+        SymmetricCipher::Aes256 => aes_crypt::<aes::Aes256>(mode, params, data, decrypt),
+        SymmetricCipher::ChaCha20Poly1305 => {
+            let c = ChaCha20Poly1305::new_from_slice(params.key).map_err(|_| key_len(params))?;
+            aead(&c, params, data, decrypt)
+        },
+    }
+}
+
+fn aes_crypt<C>(
+    mode: BlockMode,
+    params: &Params,
+    data: &[u8],
+    decrypt: bool,
+) -> Result<Vec<u8>, String>
+where
+    C: BlockCipher + BlockSizeUser<BlockSize = U16> + BlockEncrypt + BlockDecrypt + KeyInit,
+{
+    let (key, iv) = (params.key, params.iv);
+    match (mode, decrypt) {
+        (BlockMode::Ecb, false) => Ok(ecb::Encryptor::<C>::new_from_slice(key)
+            .map_err(|_| key_len(params))?
+            .encrypt_padded_vec_mut::<Pkcs7>(data)),
+        (BlockMode::Ecb, true) => ecb::Decryptor::<C>::new_from_slice(key)
+            .map_err(|_| key_len(params))?
+            .decrypt_padded_vec_mut::<Pkcs7>(data)
+            .map_err(|_| "invalid padding or block length".to_owned()),
+        (BlockMode::Cbc, false) => Ok(cbc::Encryptor::<C>::new_from_slices(key, iv)
+            .map_err(|_| key_iv_len(params))?
+            .encrypt_padded_vec_mut::<Pkcs7>(data)),
+        (BlockMode::Cbc, true) => cbc::Decryptor::<C>::new_from_slices(key, iv)
+            .map_err(|_| key_iv_len(params))?
+            .decrypt_padded_vec_mut::<Pkcs7>(data)
+            .map_err(|_| "invalid padding or block length".to_owned()),
+        (BlockMode::Ctr, _) => {
+            let mut buf = data.to_vec();
+            ctr::Ctr128BE::<C>::new_from_slices(key, iv)
+                .map_err(|_| key_iv_len(params))?
+                .apply_keystream(&mut buf);
+            Ok(buf)
+        },
+        (BlockMode::Gcm, _) => {
+            let c = AesGcm::<C, U12>::new_from_slice(key).map_err(|_| key_len(params))?;
+            aead(&c, params, data, decrypt)
+        },
+    }
+}
+
+fn aead<A: Aead>(
+    cipher: &A,
+    params: &Params,
+    data: &[u8],
+    decrypt: bool,
+) -> Result<Vec<u8>, String> {
+    if params.iv.len() != A::NonceSize::USIZE {
+        return Err(format!(
+            "invalid nonce length: {} bytes (expected {})",
+            params.iv.len(),
+            A::NonceSize::USIZE
+        ));
+    }
+    let nonce = Nonce::<A>::from_slice(params.iv);
+    let payload = Payload {
+        msg: data,
+        aad: params.aad,
+    };
+    match decrypt {
+        false => cipher.encrypt(nonce, payload),
+        true => cipher.decrypt(nonce, payload),
+    }
+    .map_err(|_| "authentication failed".to_owned())
+}
+
+fn key_len(params: &Params) -> String { format!("invalid key length: {} bytes", params.key.len()) }
+
+fn key_iv_len(params: &Params) -> String {
+    format!(
+        "invalid key or iv length: {} / {} bytes",
+        params.key.len(),
+        params.iv.len()
+    )
+}
@@ -0,0 +1,173 @@
+use crate::conv::data::DataError;
+use crate::conv::enum_variants::{Delimiter, Quoting};
+use serde_json::{Map, Value};
+
+#[derive(Clone, PartialEq)]
+pub struct CsvParams {
+    pub delimiter: Delimiter,
+    pub quoting: Quoting,
+    /// the first row holds the column names
+    pub header: bool,
+}
+
+impl Default for CsvParams {
+    fn default() -> Self {
+        Self {
+            delimiter: Delimiter::default(),
+            quoting: Quoting::default(),
+            header: true,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Table {
+    pub headers: Option<Vec<String>>,
+    pub rows: Vec<Vec<String>>,
+}
+
+fn delimiter(d: Delimiter) -> u8 {
+    match d {
+        Delimiter::Comma => b',',
+        Delimiter::Tab => b'\t',
+        Delimiter::Semicolon => b';',
+        Delimiter::Pipe => b'|',
+    }
+}
+
+pub fn parse_csv(text: &str, params: &CsvParams) -> Result<Table, DataError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter(params.delimiter))
+        .has_headers(params.header)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let mut table = Table::default();
+    if params.header {
+        let headers = reader.headers().map_err(csv_error)?;
+        table.headers = Some(headers.iter().map(str::to_owned).collect());
+    }
+    for (i, record) in reader.records().enumerate() {
+        let record = record.map_err(csv_error)?;
+        // a ragged row would lose cells or keys once keyed by the header
+        if let Some(headers) = table.headers.as_ref().filter(|h| h.len() != record.len()) {
+            return Err(DataError {
+                message: format!(
+                    "row {} has {} cells, the header has {}",
+                    i + 1,
+                    record.len(),
+                    headers.len()
+                ),
+                position: record.position().map(|p| (p.line() as usize, 1)),
+            });
+        }
+        table.rows.push(record.iter().map(str::to_owned).collect());
+    }
+    Ok(table)
+}
+
+/// objects keyed by the header row, or arrays without one
+pub fn to_json(table: &Table) -> String {
+    let rows = table
+        .rows
+        .iter()
+        .map(|row| match &table.headers {
+            Some(headers) => Value::Object(
+                headers
+                    .iter()
+                    .zip(row.iter())
+                    .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+                    .collect::<Map<_, _>>(),
+            ),
+            None => Value::Array(row.iter().cloned().map(Value::String).collect()),
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string_pretty(&rows).unwrap_or_default()
+}
+
+/// an array of objects or an array of arrays
+pub fn parse_json(text: &str) -> Result<Table, DataError> {
+    let Value::Array(items) = serde_json::from_str::<Value>(text)? else {
+        return Err(DataError {
+            message: "expected an array of objects or arrays".to_owned(),
+            position: None,
+        });
+    };
+
+    let mut table = Table::default();
+    if items.iter().any(Value::is_object) {
+        if let Some(i) = items.iter().position(|item| !item.is_object()) {
+            return Err(DataError {
+                message: format!("item {} is not an object like the others", i),
+                position: None,
+            });
+        }
+        // columns in order of first appearance
+        let mut headers: Vec<String> = vec![];
+        for item in &items {
+            if let Value::Object(o) = item {
+                for k in o.keys() {
+                    if !headers.contains(k) {
+                        headers.push(k.clone());
+                    }
+                }
+            }
+        }
+        table.rows = items
+            .iter()
+            .map(|item| {
+                headers
+                    .iter()
+                    .map(|k| item.get(k).map(cell).unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+        table.headers = Some(headers);
+    } else {
+        table.rows = items
+            .iter()
+            .map(|item| match item {
+                Value::Array(a) => a.iter().map(cell).collect(),
+                v => vec![cell(v)],
+            })
+            .collect();
+    }
+    Ok(table)
+}
+
+pub fn to_csv(table: &Table, params: &CsvParams) -> Result<String, String> {
+    let quote_style = match params.quoting {
+        Quoting::Necessary => csv::QuoteStyle::Necessary,
+        Quoting::Always => csv::QuoteStyle::Always,
+        Quoting::NonNumeric => csv::QuoteStyle::NonNumeric,
+        Quoting::Never => csv::QuoteStyle::Never,
+    };
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter(params.delimiter))
+        .quote_style(quote_style)
+        .flexible(true)
+        .from_writer(vec![]);
+    if let (true, Some(headers)) = (params.header, &table.headers) {
+        writer.write_record(headers).map_err(|e| e.to_string())?;
+    }
+    for row in &table.rows {
+        writer.write_record(row).map_err(|e| e.to_string())?;
+    }
+    let buf = writer.into_inner().map_err(|e| e.to_string())?;
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+/// nested values are kept as json text
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    }
+}
+
+fn csv_error(e: csv::Error) -> DataError {
+    DataError {
+        position: e.position().map(|p| (p.line() as usize, 1)),
+        message: e.to_string(),
+    }
+}
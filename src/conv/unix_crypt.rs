@@ -0,0 +1,268 @@
+use password_hash::rand_core::{OsRng, RngCore};
+use sha2::Digest;
+
+const ITOA64: &[u8; 64] = b"./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+#[rustfmt::skip]
+const MD5_ORDER: [(usize, usize, usize); 5] = [
+    (0, 6, 12), (1, 7, 13), (2, 8, 14), (3, 9, 15), (4, 10, 5),
+];
+
+#[rustfmt::skip]
+const SHA256_ORDER: [(usize, usize, usize); 10] = [
+    (0, 10, 20), (21, 1, 11), (12, 22, 2), (3, 13, 23), (24, 4, 14),
+    (15, 25, 5), (6, 16, 26), (27, 7, 17), (18, 28, 8), (9, 19, 29),
+];
+
+#[rustfmt::skip]
+const SHA512_ORDER: [(usize, usize, usize); 21] = [
+    (0, 21, 42), (22, 43, 1), (44, 2, 23), (3, 24, 45), (25, 46, 4),
+    (47, 5, 26), (6, 27, 48), (28, 49, 7), (50, 8, 29), (9, 30, 51),
+    (31, 52, 10), (53, 11, 32), (12, 33, 54), (34, 55, 13), (56, 14, 35),
+    (15, 36, 57), (37, 58, 16), (59, 17, 38), (18, 39, 60), (40, 61, 19),
+    (62, 20, 41),
+];
+
+const SHA_ROUNDS_DEFAULT: u32 = 5000;
+
+#[derive(Copy, Clone)]
+pub enum ShaCrypt {
+    Sha256,
+    Sha512,
+}
+
+impl ShaCrypt {
+    fn id(self) -> &'static str {
+        match self {
+            ShaCrypt::Sha256 => "5",
+            ShaCrypt::Sha512 => "6",
+        }
+    }
+}
+
+pub fn gen_salt(len: usize) -> String {
+    let mut buf = vec![0; len];
+    OsRng.fill_bytes(&mut buf);
+    buf.iter().map(|x| ITOA64[(x & 0x3f) as usize] as char).collect()
+}
+
+/// MD5-crypt, id is "1" for crypt(3) or "apr1" for apache htpasswd
+pub fn md5_crypt(pw: &[u8], salt: &str, id: &str) -> String {
+    let salt = &salt.as_bytes()[..salt.len().min(8)];
+    let magic = format!("${}$", id);
+
+    let mut alt = md5::Context::new();
+    alt.consume(pw);
+    alt.consume(salt);
+    alt.consume(pw);
+    let alt = alt.compute().0;
+
+    let mut ctx = md5::Context::new();
+    ctx.consume(pw);
+    ctx.consume(&magic);
+    ctx.consume(salt);
+    for chunk in pw.chunks(16) {
+        ctx.consume(&alt[..chunk.len()]);
+    }
+    let mut i = pw.len();
+    while i > 0 {
+        match i & 1 {
+            1 => ctx.consume([0u8]),
+            _ => ctx.consume(&pw[..1]),
+        }
+        i >>= 1;
+    }
+    let mut fin = ctx.compute().0;
+
+    for r in 0..1000 {
+        let mut ctx = md5::Context::new();
+        match r & 1 {
+            1 => ctx.consume(pw),
+            _ => ctx.consume(fin),
+        }
+        if r % 3 != 0 {
+            ctx.consume(salt);
+        }
+        if r % 7 != 0 {
+            ctx.consume(pw);
+        }
+        match r & 1 {
+            1 => ctx.consume(fin),
+            _ => ctx.consume(pw),
+        }
+        fin = ctx.compute().0;
+    }
+
+    let mut out = format!("{}{}$", magic, String::from_utf8_lossy(salt));
+    encode(&mut out, &fin, &MD5_ORDER);
+    b64_from_24bit(&mut out, 0, 0, fin[11], 2);
+    out
+}
+
+/// SHA-crypt, "$5$" for SHA-256 or "$6$" for SHA-512
+pub fn sha_crypt(alg: ShaCrypt, pw: &[u8], salt: &str, rounds: Option<u32>) -> String {
+    let salt = &salt.as_bytes()[..salt.len().min(16)];
+    let rounds = rounds.map(|r| r.clamp(1000, 999_999_999));
+
+    let mut out = format!("${}$", alg.id());
+    if let Some(r) = rounds {
+        out.push_str(&format!("rounds={}$", r));
+    }
+    out.push_str(&format!("{}$", String::from_utf8_lossy(salt)));
+
+    let rounds = rounds.unwrap_or(SHA_ROUNDS_DEFAULT);
+    match alg {
+        ShaCrypt::Sha256 => {
+            let c = sha_crypt_raw::<sha2::Sha256>(pw, salt, rounds);
+            encode(&mut out, &c, &SHA256_ORDER);
+            b64_from_24bit(&mut out, 0, c[31], c[30], 3);
+        },
+        ShaCrypt::Sha512 => {
+            let c = sha_crypt_raw::<sha2::Sha512>(pw, salt, rounds);
+            encode(&mut out, &c, &SHA512_ORDER);
+            b64_from_24bit(&mut out, 0, 0, c[63], 2);
+        },
+    }
+    out
+}
+
+/// returns None when the hash is not a crypt(3) hash
+pub fn verify(pw: &[u8], hash: &str) -> Option<bool> {
+    let (id, rest) = hash.strip_prefix('$')?.split_once('$')?;
+    match id {
+        "1" | "apr1" => {
+            let salt = rest.split('$').next()?;
+            Some(md5_crypt(pw, salt, id) == hash)
+        },
+        "5" | "6" => {
+            let alg = if id == "5" { ShaCrypt::Sha256 } else { ShaCrypt::Sha512 };
+            let (rounds, rest) = match rest.strip_prefix("rounds=") {
+                Some(r) => {
+                    let (n, rest) = r.split_once('$')?;
+                    (Some(n.parse().ok()?), rest)
+                },
+                None => (None, rest),
+            };
+            let salt = rest.split('$').next()?;
+            Some(sha_crypt(alg, pw, salt, rounds) == hash)
+        },
+        _ => None,
+    }
+}
+
+fn sha_crypt_raw<D: Digest>(pw: &[u8], salt: &[u8], rounds: u32) -> Vec<u8> {
+    let b = D::new()
+        .chain_update(pw)
+        .chain_update(salt)
+        .chain_update(pw)
+        .finalize();
+
+    let mut ctx = D::new();
+    ctx.update(pw);
+    ctx.update(salt);
+    for chunk in pw.chunks(b.len()) {
+        ctx.update(&b[..chunk.len()]);
+    }
+    let mut i = pw.len();
+    while i > 0 {
+        match i & 1 {
+            1 => ctx.update(&b),
+            _ => ctx.update(pw),
+        }
+        i >>= 1;
+    }
+    let a = ctx.finalize();
+
+    let mut dp = D::new();
+    (0..pw.len()).for_each(|_| dp.update(pw));
+    let p = dp.finalize().iter().copied().cycle().take(pw.len()).collect::<Vec<_>>();
+
+    let mut ds = D::new();
+    (0..16 + a[0] as usize).for_each(|_| ds.update(salt));
+    let s = ds.finalize().iter().copied().cycle().take(salt.len()).collect::<Vec<_>>();
+
+    let mut c = a.to_vec();
+    for r in 0..rounds {
+        let mut ctx = D::new();
+        match r & 1 {
+            1 => ctx.update(&p),
+            _ => ctx.update(&c),
+        }
+        if r % 3 != 0 {
+            ctx.update(&s);
+        }
+        if r % 7 != 0 {
+            ctx.update(&p);
+        }
+        match r & 1 {
+            1 => ctx.update(&c),
+            _ => ctx.update(&p),
+        }
+        c = ctx.finalize().to_vec();
+    }
+    c
+}
+
+fn encode(out: &mut String, c: &[u8], order: &[(usize, usize, usize)]) {
+    for (b2, b1, b0) in order {
+        b64_from_24bit(out, c[*b2], c[*b1], c[*b0], 4);
+    }
+}
+
+fn b64_from_24bit(out: &mut String, b2: u8, b1: u8, b0: u8, n: usize) {
+    let mut w = ((b2 as u32) << 16) | ((b1 as u32) << 8) | b0 as u32;
+    for _ in 0..n {
+        out.push(ITOA64[(w & 0x3f) as usize] as char);
+        w >>= 6;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PW: &[u8] = b"Hello world!";
+
+    #[test]
+    fn md5() {
+        assert_eq!(md5_crypt(PW, "saltstring", "1"), "$1$saltstri$YMyguxXMBpd2TEZ.vS/3q1");
+        assert_eq!(md5_crypt(PW, "saltstri", "apr1"), "$apr1$saltstri$aGfuB7Lcvs2TUeFTqUVfN0");
+    }
+
+    // test vectors of the SHA-crypt specification
+    #[test]
+    fn sha256() {
+        assert_eq!(
+            sha_crypt(ShaCrypt::Sha256, PW, "saltstring", None),
+            "$5$saltstring$5B8vYYiY.CVt1RlTTf8KbXBH3hsxY/GNooZaBBGWEc5"
+        );
+        assert_eq!(
+            sha_crypt(ShaCrypt::Sha256, PW, "saltstringsaltstring", Some(10000)),
+            "$5$rounds=10000$saltstringsaltst$3xv.VbSHBb41AL9AvLeujZkZRBAwqFMz2.opqey6IcA"
+        );
+    }
+
+    #[test]
+    fn sha512() {
+        assert_eq!(
+            sha_crypt(ShaCrypt::Sha512, PW, "saltstring", None),
+            "$6$saltstring$svn8UoSVapNtMuq1ukKS4tPQd8iKwSMHWjl/O817G3uBnIFNjnQJuesI68u4OTLiBFdcbYEd\
+             FCoEOfaS35inz1"
+        );
+        let pw = b"the minimum number is still observed";
+        assert_eq!(
+            sha_crypt(ShaCrypt::Sha512, pw, "roundstoolow", Some(10)),
+            "$6$rounds=1000$roundstoolow$kUMsbe306n21p9R.FRkW3IGn.S9NPN0x50YhH1xhLsPuWGsUSklZt58ja\
+             TfF4ZEQpyUNGc0dqbpBYYBaHHrsX."
+        );
+    }
+
+    #[test]
+    fn verify_hashes() {
+        assert_eq!(verify(PW, "$1$saltstri$YMyguxXMBpd2TEZ.vS/3q1"), Some(true));
+        assert_eq!(verify(b"hello world!", "$1$saltstri$YMyguxXMBpd2TEZ.vS/3q1"), Some(false));
+        let hash = "$5$rounds=10000$saltstringsaltst$3xv.VbSHBb41AL9AvLeujZkZRBAwqFMz2.opqey6IcA";
+        assert_eq!(verify(PW, hash), Some(true));
+        assert_eq!(verify(PW, "$2b$10$abc"), None);
+    }
+}
@@ -0,0 +1,374 @@
+use crate::conv::asn1::{self, Class, Node, BOOLEAN, INTEGER, OCTET_STRING, OID, SEQUENCE};
+use crate::conv::enum_variants::{Digest, DigestFormat};
+use crate::conv::hasher::{digest_bytes, format_digest};
+use crate::conv::time::{parse_time, unix_now};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// subject, issuer, validity, key and extensions of every certificate or CSR in the input
+pub fn inspect(text: &str) -> Result<String, String> {
+    let now = unix_now();
+    let mut blocks = vec![];
+    for (label, der) in asn1::input(text)? {
+        let nodes = asn1::parse(&der)?;
+        let root = nodes.first().ok_or("empty input")?;
+        let lines = match label.contains("REQUEST") || is_csr(root) {
+            true => csr(root),
+            false => certificate(root, &der, now),
+        };
+        blocks.push(lines.unwrap_or_else(|e| match label.as_str() {
+            "" => e,
+            label => format!("{}: {}", label, e),
+        }));
+    }
+    Ok(blocks.join("\n\n"))
+}
+
+/// CertificationRequestInfo has four elements, TBSCertificate at least six
+fn is_csr(root: &Node) -> bool {
+    root.child(0)
+        .is_ok_and(|info| info.children.len() == 4 && info.children[3].is_context(0))
+}
+
+fn certificate(root: &Node, der: &[u8], now: i64) -> Result<String, String> {
+    let tbs = expect(root.child(0)?, SEQUENCE, "TBSCertificate")?;
+    let mut fields = tbs.children.iter().peekable();
+    let version = match fields.next_if(|n| n.is_context(0)) {
+        Some(v) => version(v.child(0)?)?,
+        None => 1,
+    };
+    let mut next = |what: &str| {
+        fields
+            .next()
+            .ok_or_else(|| format!("TBSCertificate has no {}", what))
+    };
+    let serial = expect(next("serial number")?, INTEGER, "serial number")?;
+    let _signature = next("signature algorithm")?;
+    let issuer = next("issuer")?;
+    let validity = expect(next("validity")?, SEQUENCE, "validity")?;
+    let subject = next("subject")?;
+    let spki = next("subject public key info")?;
+    let extensions = fields
+        .find(|n| n.is_context(3))
+        .map(|n| n.child(0))
+        .transpose()?;
+
+    let mut lines = vec!["Certificate".to_owned()];
+    lines.push(format!("  Version: {}", version));
+    lines.push(format!("  Serial: {}", hex_colons(serial.content)));
+    lines.push(format!("  Signature: {}", algorithm(root.child(1)?)?));
+    lines.push(format!("  Issuer: {}", name(issuer)?));
+    lines.push(format!("  Subject: {}", name(subject)?));
+    let not_before = time(validity.child(0)?)?;
+    let not_after = time(validity.child(1)?)?;
+    lines.push(format!("  Not Before: {}", not_before.replace('T', " ").replace('Z', " UTC")));
+    lines.push(format!("  Not After: {}", not_after.replace('T', " ").replace('Z', " UTC")));
+    lines.push(format!("  Status: {}", status(&not_before, &not_after, now)));
+    lines.push(format!("  Public Key: {}", public_key(spki)?));
+    if let Some(extensions) = extensions {
+        lines.push("Extensions".to_owned());
+        lines.extend(render_extensions(extensions)?);
+    }
+    let der = root.der(der);
+    lines.push("Fingerprints".to_owned());
+    for (label, digest) in [("SHA-1", Digest::Sha1), ("SHA-256", Digest::Sha256)] {
+        let fingerprint = format_digest(&digest_bytes(digest, der), DigestFormat::Fingerprint);
+        lines.push(format!("  {}: {}", label, fingerprint));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn csr(root: &Node) -> Result<String, String> {
+    let info = expect(root.child(0)?, SEQUENCE, "CertificationRequestInfo")?;
+    let version = version(info.child(0)?)?;
+    let mut lines = vec!["Certificate Request".to_owned()];
+    lines.push(format!("  Version: {}", version));
+    lines.push(format!("  Signature: {}", algorithm(root.child(1)?)?));
+    lines.push(format!("  Subject: {}", name(info.child(1)?)?));
+    lines.push(format!("  Public Key: {}", public_key(info.child(2)?)?));
+    let mut extensions = vec![];
+    for attribute in &info.child(3)?.children {
+        let oid = asn1::oid(expect(attribute.child(0)?, OID, "attribute type")?.content);
+        let values = &attribute.child(1)?.children;
+        match oid.as_str() {
+            // extensionRequest
+            "1.2.840.113549.1.9.14" => {
+                for requested in values {
+                    extensions.extend(render_extensions(requested)?);
+                }
+            },
+            _ => {
+                let values = values.iter().map(Node::text).collect::<Vec<_>>();
+                let label = asn1::oid_name(&oid).unwrap_or(&oid);
+                lines.push(format!("  {}: {}", label, values.join(", ")));
+            },
+        }
+    }
+    if !extensions.is_empty() {
+        lines.push("Requested Extensions".to_owned());
+        lines.extend(extensions);
+    }
+    Ok(lines.join("\n"))
+}
+
+fn expect<'n, 'a>(node: &'n Node<'a>, tag: u32, what: &str) -> Result<&'n Node<'a>, String> {
+    match node.is(tag) {
+        true => Ok(node),
+        false => Err(format!("expected {} at offset {}", what, node.offset)),
+    }
+}
+
+/// certificate and request versions are stored zero based, v3 is INTEGER 2
+fn version(node: &Node) -> Result<u32, String> {
+    match expect(node, INTEGER, "version")?.content {
+        [v] => Ok(u32::from(*v) + 1),
+        c => Err(format!("unsupported version {} at offset {}", asn1::integer(c), node.offset)),
+    }
+}
+
+fn algorithm(node: &Node) -> Result<String, String> {
+    let oid = expect(node.child(0)?, OID, "algorithm identifier")?;
+    Ok(asn1::oid_name(&asn1::oid(oid.content))
+        .map_or_else(|| asn1::oid(oid.content), str::to_owned))
+}
+
+/// rfc 4514 style, most significant first like openssl, ex: 'C=US, O=Let's Encrypt, CN=R3'
+fn name(node: &Node) -> Result<String, String> {
+    let mut parts = vec![];
+    for rdn in &expect(node, SEQUENCE, "name")?.children {
+        for attribute in &rdn.children {
+            let oid = asn1::oid(expect(attribute.child(0)?, OID, "attribute type")?.content);
+            let key = short_name(&oid).or(asn1::oid_name(&oid)).unwrap_or(&oid);
+            parts.push(format!("{}={}", key, attribute.child(1)?.text()));
+        }
+    }
+    Ok(parts.join(", "))
+}
+
+fn short_name(oid: &str) -> Option<&'static str> {
+    Some(match oid {
+        "2.5.4.3" => "CN",
+        "2.5.4.6" => "C",
+        "2.5.4.7" => "L",
+        "2.5.4.8" => "ST",
+        "2.5.4.9" => "street",
+        "2.5.4.10" => "O",
+        "2.5.4.11" => "OU",
+        "0.9.2342.19200300.100.1.1" => "UID",
+        "0.9.2342.19200300.100.1.25" => "DC",
+        _ => return None,
+    })
+}
+
+/// UTCTime or GeneralizedTime as xs:dateTime, ex: '2024-01-02T03:04:05Z'
+fn time(node: &Node) -> Result<String, String> {
+    let t = String::from_utf8_lossy(node.content);
+    let t = t.trim_end_matches('Z');
+    let t = t.split_once('.').map_or(t, |(t, _)| t);
+    if !t.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("invalid time {:?} at offset {}", t, node.offset));
+    }
+    let full = match (node.tag, t.len()) {
+        (23, 12) => format!("{}{}", if &t[..2] < "50" { "20" } else { "19" }, t),
+        (24, 14) => t.to_owned(),
+        _ => return Err(format!("unsupported time {:?} at offset {}", t, node.offset)),
+    };
+    Ok(format!(
+        "{}-{}-{}T{}:{}:{}Z",
+        &full[..4],
+        &full[4..6],
+        &full[6..8],
+        &full[8..10],
+        &full[10..12],
+        &full[12..14]
+    ))
+}
+
+fn status(not_before: &str, not_after: &str, now: i64) -> String {
+    const DAY: i64 = 86400;
+    match (parse_time(not_before), parse_time(not_after)) {
+        (Some(t), _) if now < t => format!("⚠ not yet valid, starts in {} days", (t - now) / DAY),
+        (_, Some(t)) if now >= t => format!("⚠ expired {} days ago", (now - t) / DAY),
+        (_, Some(t)) => format!("valid, expires in {} days", (t - now) / DAY),
+        _ => "unknown".to_owned(),
+    }
+}
+
+/// key type and size from a SubjectPublicKeyInfo
+fn public_key(spki: &Node) -> Result<String, String> {
+    let alg = expect(spki.child(0)?, SEQUENCE, "key algorithm")?;
+    let oid = asn1::oid(expect(alg.child(0)?, OID, "key algorithm")?.content);
+    let key = spki.child(1)?;
+    Ok(match oid.as_str() {
+        "1.2.840.113549.1.1.1" => {
+            let rsa = key.child(0)?;
+            let n = expect(rsa.child(0)?, INTEGER, "modulus")?;
+            let e = expect(rsa.child(1)?, INTEGER, "exponent")?;
+            format!("RSA {} bits (e={})", bit_length(n.content), asn1::integer(e.content))
+        },
+        "1.2.840.10045.2.1" => {
+            let curve = alg.child(1)?;
+            let curve = asn1::oid(expect(curve, OID, "named curve")?.content);
+            let bits = match curve.as_str() {
+                "1.2.840.10045.3.1.7" | "1.3.132.0.10" => "256",
+                "1.3.132.0.34" => "384",
+                "1.3.132.0.35" => "521",
+                _ => "?",
+            };
+            format!("EC {} ({} bits)", asn1::oid_name(&curve).unwrap_or(&curve), bits)
+        },
+        "1.2.840.10040.4.1" => {
+            let p = expect(alg.child(1)?.child(0)?, INTEGER, "DSA p")?;
+            format!("DSA {} bits", bit_length(p.content))
+        },
+        "1.3.101.112" => "Ed25519 (256 bits)".to_owned(),
+        "1.3.101.113" => "Ed448 (456 bits)".to_owned(),
+        "1.3.101.110" => "X25519 (256 bits)".to_owned(),
+        "1.3.101.111" => "X448 (448 bits)".to_owned(),
+        _ => format!("{} ({} bytes)", asn1::oid_name(&oid).unwrap_or(&oid), key.bits().len()),
+    })
+}
+
+fn bit_length(integer: &[u8]) -> usize {
+    let digits = match integer.iter().position(|&b| b != 0) {
+        Some(i) => &integer[i..],
+        None => return 0,
+    };
+    digits.len() * 8 - digits[0].leading_zeros() as usize
+}
+
+fn render_extensions(extensions: &Node) -> Result<Vec<String>, String> {
+    let mut lines = vec![];
+    for extension in &expect(extensions, SEQUENCE, "extensions")?.children {
+        let oid = asn1::oid(expect(extension.child(0)?, OID, "extension id")?.content);
+        let mut rest = extension.children[1..].iter().peekable();
+        let critical = rest
+            .next_if(|n| n.is(BOOLEAN))
+            .is_some_and(|n| n.content != [0]);
+        let value = expect(rest.next().ok_or("extension without a value")?, OCTET_STRING, "value")?;
+        let label = match asn1::oid_name(&oid) {
+            Some(name) => name.to_owned(),
+            None => oid.clone(),
+        };
+        let label = match critical {
+            true => format!("{} (critical)", label),
+            false => label,
+        };
+        let text = value
+            .children
+            .first()
+            .and_then(|inner| extension_value(&oid, inner))
+            .unwrap_or_else(|| hex_colons(value.content));
+        lines.push(format!("  {}: {}", label, text));
+    }
+    Ok(lines)
+}
+
+fn extension_value(oid: &str, v: &Node) -> Option<String> {
+    Some(match oid {
+        // subject / issuer alternative name
+        "2.5.29.17" | "2.5.29.18" => general_names(v),
+        "2.5.29.19" => {
+            let mut parts = vec![];
+            let ca = v.children.first().is_some_and(|n| n.is(BOOLEAN) && n.content != [0]);
+            parts.push(format!("CA:{}", if ca { "TRUE" } else { "FALSE" }));
+            if let Some(n) = v.children.iter().find(|n| n.is(INTEGER)) {
+                parts.push(format!("pathlen:{}", asn1::integer(n.content)));
+            }
+            parts.join(", ")
+        },
+        "2.5.29.15" => {
+            const USAGES: [&str; 9] = [
+                "Digital Signature",
+                "Non Repudiation",
+                "Key Encipherment",
+                "Data Encipherment",
+                "Key Agreement",
+                "Certificate Sign",
+                "CRL Sign",
+                "Encipher Only",
+                "Decipher Only",
+            ];
+            let bits = v.bits();
+            let set = |i: usize| bits.get(i / 8).is_some_and(|b| b >> (7 - i % 8) & 1 == 1);
+            USAGES
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| set(*i))
+                .map(|(_, u)| *u)
+                .collect::<Vec<_>>()
+                .join(", ")
+        },
+        // extended key usage, certificate policies
+        "2.5.29.37" | "2.5.29.32" => v
+            .children
+            .iter()
+            .filter_map(|n| match n.is(OID) {
+                true => Some(n),
+                false => n.children.first().filter(|n| n.is(OID)),
+            })
+            .map(|n| {
+                let oid = asn1::oid(n.content);
+                asn1::oid_name(&oid).map_or(oid, str::to_owned)
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        "2.5.29.14" => hex_colons(v.content),
+        "2.5.29.35" => {
+            let id = v.children.iter().find(|n| n.is_context(0))?;
+            format!("keyid:{}", hex_colons(id.content))
+        },
+        // authority information access
+        "1.3.6.1.5.5.7.1.1" => v
+            .children
+            .iter()
+            .filter_map(|access| {
+                let method = asn1::oid(access.children.first()?.content);
+                let method = asn1::oid_name(&method).map_or(method, str::to_owned);
+                Some(format!("{} - {}", method, general_name(access.children.get(1)?)))
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        // crl distribution points, every URI in the full names
+        "2.5.29.31" => {
+            let mut uris = vec![];
+            collect_uris(v, &mut uris);
+            uris.join(", ")
+        },
+        _ => return None,
+    })
+}
+
+fn general_names(v: &Node) -> String {
+    v.children.iter().map(general_name).collect::<Vec<_>>().join(", ")
+}
+
+fn general_name(n: &Node) -> String {
+    let text = || String::from_utf8_lossy(n.content).into_owned();
+    match (n.class, n.tag) {
+        (Class::Context, 1) => format!("email:{}", text()),
+        (Class::Context, 2) => format!("DNS:{}", text()),
+        (Class::Context, 6) => format!("URI:{}", text()),
+        (Class::Context, 7) => match n.content.len() {
+            4 => format!("IP:{}", Ipv4Addr::from(<[u8; 4]>::try_from(n.content).unwrap())),
+            16 => format!("IP:{}", Ipv6Addr::from(<[u8; 16]>::try_from(n.content).unwrap())),
+            _ => format!("IP:{}", hex_colons(n.content)),
+        },
+        (Class::Context, 4) => match n.children.first().map(name) {
+            Some(Ok(dn)) => format!("DirName:{}", dn),
+            _ => format!("DirName:{}", hex_colons(n.content)),
+        },
+        (Class::Context, 8) => format!("RID:{}", asn1::oid(n.content)),
+        _ => format!("othername:{}", hex_colons(n.content)),
+    }
+}
+
+fn collect_uris(n: &Node, uris: &mut Vec<String>) {
+    if n.is_context(6) && !n.constructed {
+        uris.push(format!("URI:{}", String::from_utf8_lossy(n.content)));
+    }
+    for child in &n.children {
+        collect_uris(child, uris);
+    }
+}
+
+fn hex_colons(bytes: &[u8]) -> String { format_digest(bytes, DigestFormat::Fingerprint) }
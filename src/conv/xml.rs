@@ -0,0 +1,280 @@
+use crate::conv::data::{line_column, DataError};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, Writer};
+use std::collections::BTreeMap;
+
+type Namespaces = BTreeMap<String, String>;
+
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+#[derive(Clone, PartialEq)]
+pub struct XmlParams {
+    pub indent: usize,
+}
+
+impl Default for XmlParams {
+    fn default() -> Self { Self { indent: 2 } }
+}
+
+pub fn pretty(xml: &str, params: &XmlParams) -> Result<String, DataError> {
+    let writer = Writer::new_with_indent(Vec::new(), b' ', params.indent);
+    rewrite(xml, writer, true)
+}
+
+/// drops comments and whitespace between elements
+pub fn minify(xml: &str) -> Result<String, DataError> {
+    rewrite(xml, Writer::new(Vec::new()), false)
+}
+
+fn rewrite(xml: &str, mut writer: Writer<Vec<u8>>, comments: bool) -> Result<String, DataError> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Eof) => break,
+            Ok(Event::Comment(_)) if !comments => {},
+            Ok(e) => writer.write_event(e).map_err(|e| DataError {
+                message: e.to_string(),
+                position: None,
+            })?,
+            Err(e) => return Err(error(xml, &reader, e)),
+        }
+    }
+    Ok(String::from_utf8_lossy(&writer.into_inner()).into_owned())
+}
+
+/// Exclusive XML Canonicalization 1.0, without comments
+/// https://www.w3.org/TR/xml-exc-c14n/
+pub fn exc_c14n(xml: &str) -> Result<String, DataError> {
+    // line ends are normalised by xml parsers before canonicalization
+    let xml = xml.replace("\r\n", "\n").replace('\r', "\n");
+    let mut reader = Reader::from_str(&xml);
+    let mut buf = String::new();
+    // namespaces in scope in the input, and rendered in the output
+    let mut in_scope: Vec<Namespaces> = vec![Namespaces::new()];
+    let mut rendered: Vec<Namespaces> = vec![Namespaces::new()];
+    let mut seen_root = false;
+    loop {
+        let inside = in_scope.len() > 1;
+        match reader.read_event().map_err(|e| error(&xml, &reader, e))? {
+            Event::Start(e) => {
+                start_tag(&mut buf, &e, &mut in_scope, &mut rendered)?;
+                seen_root = true;
+            },
+            Event::Empty(e) => {
+                start_tag(&mut buf, &e, &mut in_scope, &mut rendered)?;
+                end_tag(&mut buf, e.name().as_ref(), &mut in_scope, &mut rendered);
+                seen_root = true;
+            },
+            Event::End(e) => end_tag(&mut buf, e.name().as_ref(), &mut in_scope, &mut rendered),
+            Event::Text(t) if inside => {
+                let text = t.unescape().map_err(|e| error(&xml, &reader, e))?;
+                escape_text(&mut buf, &text);
+            },
+            Event::CData(c) if inside => escape_text(&mut buf, &String::from_utf8_lossy(&c)),
+            Event::PI(pi) => {
+                let pi = format!("<?{}?>", String::from_utf8_lossy(&pi));
+                match (inside, seen_root) {
+                    (true, _) => buf.push_str(&pi),
+                    (false, false) => buf.push_str(&format!("{}\n", pi)),
+                    (false, true) => buf.push_str(&format!("\n{}", pi)),
+                }
+            },
+            Event::Eof => break,
+            // declaration, doctype, comments and whitespace outside the document element
+            _ => {},
+        }
+    }
+    Ok(buf)
+}
+
+fn start_tag(
+    buf: &mut String,
+    e: &BytesStart,
+    in_scope: &mut Vec<Namespaces>,
+    rendered: &mut Vec<Namespaces>,
+) -> Result<(), DataError> {
+    let mut scope = in_scope.last().cloned().unwrap_or_default();
+    let mut attrs = vec![];
+    for a in e.attributes() {
+        let a = a.map_err(|e| DataError {
+            message: e.to_string(),
+            position: None,
+        })?;
+        let key = String::from_utf8_lossy(a.key.as_ref()).into_owned();
+        // attribute value normalisation, literal whitespace becomes a space
+        let raw = String::from_utf8_lossy(&a.value).replace(['\t', '\n', '\r'], " ");
+        let value = quick_xml::escape::unescape(&raw)
+            .map_err(|e| DataError {
+                message: e.to_string(),
+                position: None,
+            })?
+            .into_owned();
+        match key.strip_prefix("xmlns") {
+            Some("") => {
+                scope.insert(String::new(), value);
+            },
+            Some(p) if p.starts_with(':') => {
+                scope.insert(p[1..].to_owned(), value);
+            },
+            _ => attrs.push((key, value)),
+        }
+    }
+
+    let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+    let uri = |p: &str| match p {
+        "xml" => XML_NAMESPACE.to_owned(),
+        p => scope.get(p).cloned().unwrap_or_default(),
+    };
+
+    // only namespaces visibly utilized by the element or its attributes
+    let mut utilized = vec![prefix(&name).to_owned()];
+    utilized.extend(
+        attrs
+            .iter()
+            .map(|(k, _)| prefix(k).to_owned())
+            .filter(|p| !p.is_empty()),
+    );
+    let mut output = rendered.last().cloned().unwrap_or_default();
+    let mut decls = Namespaces::new();
+    for p in utilized.into_iter().filter(|p| p != "xml") {
+        let uri = uri(&p);
+        let needed = match output.get(&p) {
+            Some(current) => *current != uri,
+            None => !(p.is_empty() && uri.is_empty()),
+        };
+        if needed {
+            decls.insert(p.clone(), uri.clone());
+            output.insert(p, uri);
+        }
+    }
+    // unprefixed attributes are in no namespace, not the default one, so they sort first
+    attrs.sort_by_cached_key(|(k, _)| {
+        let uri = match prefix(k) {
+            "" => String::new(),
+            p => uri(p),
+        };
+        (uri, local(k).to_owned())
+    });
+
+    buf.push('<');
+    buf.push_str(&name);
+    for (p, uri) in decls {
+        match p.as_str() {
+            "" => buf.push_str(" xmlns=\""),
+            p => buf.push_str(&format!(" xmlns:{}=\"", p)),
+        }
+        escape_attr(buf, &uri);
+        buf.push('"');
+    }
+    for (k, v) in attrs {
+        buf.push_str(&format!(" {}=\"", k));
+        escape_attr(buf, &v);
+        buf.push('"');
+    }
+    buf.push('>');
+
+    in_scope.push(scope);
+    rendered.push(output);
+    Ok(())
+}
+
+fn end_tag(
+    buf: &mut String,
+    name: &[u8],
+    in_scope: &mut Vec<Namespaces>,
+    rendered: &mut Vec<Namespaces>,
+) {
+    buf.push_str(&format!("</{}>", String::from_utf8_lossy(name)));
+    in_scope.pop();
+    rendered.pop();
+}
+
+fn prefix(qname: &str) -> &str { qname.split_once(':').map_or("", |(p, _)| p) }
+
+fn local(qname: &str) -> &str { qname.split_once(':').map_or(qname, |(_, l)| l) }
+
+fn escape_text(buf: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => buf.push_str("&amp;"),
+            '<' => buf.push_str("&lt;"),
+            '>' => buf.push_str("&gt;"),
+            '\r' => buf.push_str("&#xD;"),
+            c => buf.push(c),
+        }
+    }
+}
+
+fn escape_attr(buf: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => buf.push_str("&amp;"),
+            '<' => buf.push_str("&lt;"),
+            '"' => buf.push_str("&quot;"),
+            '\t' => buf.push_str("&#x9;"),
+            '\n' => buf.push_str("&#xA;"),
+            '\r' => buf.push_str("&#xD;"),
+            c => buf.push(c),
+        }
+    }
+}
+
+fn error(xml: &str, reader: &Reader<&[u8]>, e: impl ToString) -> DataError {
+    DataError {
+        message: e.to_string(),
+        position: Some(line_column(xml, reader.error_position() as usize)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c14n(xml: &str) -> String { exc_c14n(xml).map_err(|e| e.message).unwrap() }
+
+    const ELEM2: &str = r#"
+   <n1:elem2 xmlns:n1="http://example.net" xml:lang="en">
+       <n3:stuff xmlns:n3="ftp://example.org"/>
+   </n1:elem2>
+"#;
+
+    const ELEM2_C14N: &str = r#"
+   <n1:elem2 xmlns:n1="http://example.net" xml:lang="en">
+       <n3:stuff xmlns:n3="ftp://example.org"></n3:stuff>
+   </n1:elem2>
+"#;
+
+    // the two documents of section 2.2 of the exclusive c14n recommendation, unused
+    // namespaces of the ancestors are dropped
+    #[test]
+    fn w3c_examples() {
+        let doc = format!(
+            r#"<n0:local xmlns:n0="foo:bar" xmlns:n3="ftp://example.org">{}</n0:local>"#,
+            ELEM2
+        );
+        assert_eq!(
+            c14n(&doc),
+            format!(r#"<n0:local xmlns:n0="foo:bar">{}</n0:local>"#, ELEM2_C14N)
+        );
+
+        let doc = format!(
+            r#"<n2:pdu xmlns:n1="http://example.com" xmlns:n2="http://foo.example"
+                xml:lang="fr" xml:space="retain">{}</n2:pdu>"#,
+            ELEM2
+        );
+        let pdu = r#"<n2:pdu xmlns:n2="http://foo.example" xml:lang="fr" xml:space="retain">"#;
+        assert_eq!(c14n(&doc), format!("{}{}</n2:pdu>", pdu, ELEM2_C14N));
+    }
+
+    #[test]
+    fn attributes_text_and_cdata() {
+        let doc = "<?xml version=\"1.0\"?>\n<!-- c -->\n<a xmlns:b=\"http://b\" b:attr=\"2\" \
+                   attr='1' xmlns=\"http://a\"><e/>&lt;&amp;&gt;\r\n<![CDATA[x<y]]></a>\n";
+        assert_eq!(
+            c14n(doc),
+            "<a xmlns=\"http://a\" xmlns:b=\"http://b\" attr=\"1\" b:attr=\"2\"><e></e>\
+             &lt;&amp;&gt;\nx&lt;y</a>"
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use itertools::Itertools;
+
+#[rustfmt::skip]
+/// relative letter frequency in english text, space included
+const ENGLISH_FREQ: [(u8, f32); 27] = [
+    (b' ', 0.1918), (b'e', 0.1041), (b't', 0.0729), (b'a', 0.0651), (b'o', 0.0596),
+    (b'n', 0.0564), (b'i', 0.0558), (b's', 0.0515), (b'r', 0.0497), (b'h', 0.0492),
+    (b'd', 0.0349), (b'l', 0.0331), (b'u', 0.0225), (b'c', 0.0217), (b'm', 0.0202),
+    (b'f', 0.0197), (b'w', 0.0171), (b'g', 0.0158), (b'y', 0.0146), (b'p', 0.0137),
+    (b'b', 0.0124), (b'v', 0.0080), (b'k', 0.0050), (b'x', 0.0014), (b'j', 0.0010),
+    (b'q', 0.0009), (b'z', 0.0006),
+];
+
+const TOP_KEYS: usize = 10;
+
+pub fn xor(data: &[u8], key: &[u8]) -> Result<Vec<u8>, String> {
+    if key.is_empty() {
+        return Err("key is empty".to_owned());
+    }
+    Ok(data.iter().zip(key.iter().cycle()).map(|(d, k)| d ^ k).collect())
+}
+
+/// tries every single byte key and lists the most english looking results
+pub fn brute_force(data: &[u8]) -> String {
+    (0..=u8::MAX)
+        .map(|k| {
+            let plain = data.iter().map(|d| d ^ k).collect::<Vec<_>>();
+            (k, score(&plain), plain)
+        })
+        .sorted_by(|a, b| b.1.total_cmp(&a.1))
+        .take(TOP_KEYS)
+        .map(|(k, score, plain)| format!("0x{:02x} ({:.2}): {}", k, score, printable(&plain)))
+        .join("\n")
+}
+
+fn score(text: &[u8]) -> f32 {
+    text.iter()
+        .map(|b| match b.to_ascii_lowercase() {
+            c @ (b' ' | b'a'..=b'z') => ENGLISH_FREQ
+                .iter()
+                .find(|(x, _)| *x == c)
+                .map_or(0.0, |(_, f)| *f),
+            b'\n' | b'\r' | b'\t' | b'!'..=b'~' => 0.0,
+            _ => -0.1,
+        })
+        .sum::<f32>()
+        / text.len().max(1) as f32
+}
+
+fn printable(text: &[u8]) -> String {
+    String::from_utf8_lossy(text)
+        .chars()
+        .map(|c| if c.is_control() { '.' } else { c })
+        .collect()
+}